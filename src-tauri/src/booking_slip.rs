@@ -0,0 +1,376 @@
+use crate::config_store::ConfigStore;
+use crate::logger::{LogLevel, Logger};
+use crate::printer::{find_printer, PrintCommand, PrintOptions};
+use crate::spooler::PrintSpooler;
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+use std::sync::Arc;
+use tauri::State;
+
+const INPUT_TIME_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S"];
+const FEED_LINES_AFTER_SLIP: usize = 6; // Keeps the footer clear of the cutter
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SlipLanguage {
+    #[default]
+    Ko,
+    En,
+}
+
+/// A seat booking as printed on the slip handed to the user.
+/// Times are local "YYYY-MM-DD HH:mm[:ss]" strings.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BookingSlip {
+    #[serde(default)]
+    pub user_name: Option<String>,
+    pub school_no: String,
+    pub room: String,
+    pub seat: String,
+    #[serde(default)]
+    pub check_in: Option<String>, // Left off with the booking date when not known
+    #[serde(default)]
+    pub expiry: Option<String>, // Left off the slip when the booking has no known end
+
+    #[serde(default)]
+    pub qr: Option<String>,
+    #[serde(default)]
+    pub language: SlipLanguage,
+}
+
+/// Fixed slip wording per language
+struct SlipText {
+    title: &'static [&'static str],
+    name: &'static str,
+    school_no: &'static str,
+    room: &'static str,
+    seat: &'static str,
+    check_in: &'static str,
+    expiry: &'static str,
+    booking_date: &'static str,
+    time_format: &'static str,
+    date_format: &'static str,
+    footer: &'static [&'static str],
+}
+
+const KO_TEXT: SlipText = SlipText {
+    title: &["서울대학교"],
+    name: "이름",
+    school_no: "학번",
+    room: "열람실",
+    seat: "좌석 번호",
+    check_in: "입실 시간",
+    expiry: "퇴실 시간",
+    booking_date: "예약 날짜",
+    time_format: "%Y년 %m월 %d일 %H:%M",
+    date_format: "%Y년 %m월 %d일",
+    footer: &[
+        "*퇴실시 좌석을 반납해 주시면.",
+        "*다른 사람들이 사용하는 데 도움이 됩니다.",
+    ],
+};
+
+const EN_TEXT: SlipText = SlipText {
+    title: &["SEOUL NATIONAL", "UNIVERSITY"],
+    name: "Name",
+    school_no: "School No",
+    room: "Room",
+    seat: "Seat No",
+    check_in: "Check in Time",
+    expiry: "Check out Time",
+    booking_date: "Booking Date",
+    time_format: "%Y-%m-%d %H:%M",
+    date_format: "%Y-%m-%d",
+    footer: &[
+        "*Please return the seat assignment when you check out.",
+        "*It helps other people use it.",
+    ],
+};
+
+fn parse_time(value: &str, field: &str) -> Result<NaiveDateTime, String> {
+    INPUT_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value.trim(), format).ok())
+        .ok_or_else(|| format!("Invalid {} time: {}", field, value))
+}
+
+/// Build the print commands for a booking slip
+pub fn booking_slip_commands(slip: &BookingSlip) -> Result<Vec<PrintCommand>, String> {
+    let text = match slip.language {
+        SlipLanguage::Ko => &KO_TEXT,
+        SlipLanguage::En => &EN_TEXT,
+    };
+    let check_in = slip
+        .check_in
+        .as_deref()
+        .filter(|check_in| !check_in.is_empty())
+        .map(|check_in| parse_time(check_in, "check-in"))
+        .transpose()?;
+    let expiry = slip
+        .expiry
+        .as_deref()
+        .filter(|expiry| !expiry.is_empty())
+        .map(|expiry| parse_time(expiry, "expiry"))
+        .transpose()?;
+
+    let mut commands = vec![
+        PrintCommand::new("clear_all", None),
+        PrintCommand::new("alignment", Some("center")),
+        PrintCommand::new("medium_text", None),
+    ];
+    for line in text.title {
+        commands.push(PrintCommand::new("wrap_text", Some(line)));
+    }
+    commands.extend([
+        PrintCommand::new("normal_text", None),
+        PrintCommand::new("blank_line", None),
+        PrintCommand::new("alignment", Some("left")),
+    ]);
+
+    if let Some(name) = slip.user_name.as_deref().filter(|name| !name.is_empty()) {
+        commands.push(PrintCommand::two_column(text.name, name));
+    }
+    commands.extend([
+        PrintCommand::two_column(text.school_no, &slip.school_no),
+        PrintCommand::two_column(text.room, &slip.room),
+        PrintCommand::two_column(text.seat, &slip.seat),
+    ]);
+    if let Some(check_in) = check_in {
+        commands.push(PrintCommand::two_column(
+            text.check_in,
+            &check_in.format(text.time_format).to_string(),
+        ));
+    }
+    if let Some(expiry) = expiry {
+        commands.push(PrintCommand::two_column(
+            text.expiry,
+            &expiry.format(text.time_format).to_string(),
+        ));
+    }
+    if let Some(check_in) = check_in {
+        commands.push(PrintCommand::two_column(
+            text.booking_date,
+            &check_in.format(text.date_format).to_string(),
+        ));
+    }
+    commands.push(PrintCommand::new("blank_line", None));
+
+    if let Some(qr) = slip.qr.as_deref().filter(|qr| !qr.is_empty()) {
+        commands.extend([
+            PrintCommand::new("alignment", Some("center")),
+            PrintCommand::new("qr_code", Some(qr)),
+            PrintCommand::new("alignment", Some("left")),
+            PrintCommand::new("blank_line", None),
+        ]);
+    }
+
+    for line in text.footer {
+        commands.push(PrintCommand::new("wrap_text", Some(line)));
+    }
+    for _ in 0..FEED_LINES_AFTER_SLIP {
+        commands.push(PrintCommand::new("blank_line", None));
+    }
+    commands.extend([
+        PrintCommand::new("cut", None),
+        PrintCommand::new("clear_all", None),
+    ]);
+
+    Ok(commands)
+}
+
+/// Queue a booking slip on the configured PRINTER; returns the print job ID
+#[tauri::command]
+pub fn print_booking_slip(
+    slip: BookingSlip,
+    spooler: State<'_, Arc<PrintSpooler>>,
+    logger: State<'_, Arc<Logger>>,
+    config: State<'_, Arc<ConfigStore>>,
+) -> Result<String, String> {
+    let logger = Arc::clone(&logger);
+    let commands = booking_slip_commands(&slip).map_err(|e| {
+        logger
+            .log_error(
+                &format!("Invalid booking slip: {}", e),
+                file!(),
+                "print_booking_slip",
+                line!(),
+            )
+            .ok();
+        e
+    })?;
+
+    let config = config.get();
+    let printer = find_printer(&config)?;
+
+    logger
+        .log(
+            LogLevel::INFO,
+            &format!(
+                "Printing booking slip for seat {} in {}",
+                slip.seat, slip.room
+            ),
+        )
+        .ok();
+
+    spooler.submit(PrintOptions {
+        port_name: printer.port.clone(),
+        baud_rate: printer.baudrate,
+        profile: printer.profile.clone(),
+        commands,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slip(language: SlipLanguage) -> BookingSlip {
+        BookingSlip {
+            user_name: Some("홍길동".to_string()),
+            school_no: "2020-12345".to_string(),
+            room: "제1 자유열람실".to_string(),
+            seat: "112".to_string(),
+            check_in: Some("2026-10-19 09:00".to_string()),
+            expiry: Some("2026-10-19 13:00:00".to_string()),
+            qr: Some("2020-12345".to_string()),
+            language,
+        }
+    }
+
+    fn rows(commands: &[PrintCommand]) -> Vec<(String, String)> {
+        commands
+            .iter()
+            .filter(|command| command.commandType == "two_column")
+            .map(|command| {
+                (
+                    command.label.clone().unwrap_or_default(),
+                    command.value.clone().unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    fn has(commands: &[PrintCommand], command_type: &str, value: &str) -> bool {
+        commands.iter().any(|command| {
+            command.commandType == command_type && command.value.as_deref() == Some(value)
+        })
+    }
+
+    #[test]
+    fn korean_slip_uses_korean_labels_and_dates() {
+        let commands = booking_slip_commands(&slip(SlipLanguage::Ko)).unwrap();
+        assert_eq!(
+            rows(&commands),
+            vec![
+                ("이름".to_string(), "홍길동".to_string()),
+                ("학번".to_string(), "2020-12345".to_string()),
+                ("열람실".to_string(), "제1 자유열람실".to_string()),
+                ("좌석 번호".to_string(), "112".to_string()),
+                (
+                    "입실 시간".to_string(),
+                    "2026년 10월 19일 09:00".to_string()
+                ),
+                (
+                    "퇴실 시간".to_string(),
+                    "2026년 10월 19일 13:00".to_string()
+                ),
+                ("예약 날짜".to_string(), "2026년 10월 19일".to_string()),
+            ]
+        );
+        assert!(has(&commands, "wrap_text", "서울대학교"));
+        assert!(has(&commands, "qr_code", "2020-12345"));
+    }
+
+    #[test]
+    fn english_slip_uses_english_labels_and_dates() {
+        let commands = booking_slip_commands(&slip(SlipLanguage::En)).unwrap();
+        let rows = rows(&commands);
+        assert_eq!(rows[0], ("Name".to_string(), "홍길동".to_string()));
+        assert_eq!(
+            rows[4],
+            ("Check in Time".to_string(), "2026-10-19 09:00".to_string())
+        );
+        assert_eq!(
+            rows[6],
+            ("Booking Date".to_string(), "2026-10-19".to_string())
+        );
+        assert!(has(&commands, "wrap_text", "SEOUL NATIONAL"));
+        assert!(has(&commands, "wrap_text", "UNIVERSITY"));
+    }
+
+    #[test]
+    fn omitted_name_expiry_and_qr_are_left_off() {
+        let mut slip = slip(SlipLanguage::En);
+        slip.user_name = None;
+        slip.expiry = Some(String::new());
+        slip.qr = None;
+        let commands = booking_slip_commands(&slip).unwrap();
+
+        let labels: Vec<String> = rows(&commands)
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                "School No",
+                "Room",
+                "Seat No",
+                "Check in Time",
+                "Booking Date"
+            ]
+        );
+        assert!(!commands
+            .iter()
+            .any(|command| command.commandType == "qr_code"));
+        assert_eq!(commands.last().unwrap().commandType, "clear_all");
+    }
+
+    #[test]
+    fn unknown_check_in_leaves_off_check_in_and_booking_date() {
+        let mut slip = slip(SlipLanguage::En);
+        slip.check_in = None;
+        let commands = booking_slip_commands(&slip).unwrap();
+
+        let labels: Vec<String> = rows(&commands)
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect();
+        assert_eq!(
+            labels,
+            vec!["Name", "School No", "Room", "Seat No", "Check out Time"]
+        );
+    }
+
+    #[test]
+    fn accepts_each_input_time_format() {
+        for check_in in [
+            "2026-10-19 09:00:30",
+            "2026-10-19 09:00",
+            "2026-10-19T09:00:30",
+            " 2026-10-19 09:00 ",
+        ] {
+            let mut slip = slip(SlipLanguage::En);
+            slip.check_in = Some(check_in.to_string());
+            let commands = booking_slip_commands(&slip).unwrap();
+            assert_eq!(rows(&commands)[4].1, "2026-10-19 09:00", "{}", check_in);
+        }
+    }
+
+    #[test]
+    fn rejects_unparseable_times() {
+        let mut bad_check_in = slip(SlipLanguage::Ko);
+        bad_check_in.check_in = Some("19/10/2026 09:00".to_string());
+        assert_eq!(
+            booking_slip_commands(&bad_check_in).unwrap_err(),
+            "Invalid check-in time: 19/10/2026 09:00"
+        );
+
+        let mut bad_expiry = slip(SlipLanguage::Ko);
+        bad_expiry.expiry = Some("13:00".to_string());
+        assert_eq!(
+            booking_slip_commands(&bad_expiry).unwrap_err(),
+            "Invalid expiry time: 13:00"
+        );
+    }
+}
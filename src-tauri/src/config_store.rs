@@ -0,0 +1,265 @@
+use crate::logger::{LogLevel, Logger};
+use crate::store::{
+    ensure_config_exists, key_patch, patch_config, read_config_file, reload_config, save_config,
+    Config, ConfigUpdate,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Where a config change came from
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeSource {
+    App,  // An update command
+    File, // The config file was edited outside the app
+}
+
+/// Payload of the `config-changed` event
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChanged {
+    keys: Vec<String>,  // Top-level keys, e.g. "serialdata"
+    paths: Vec<String>, // Changed paths, e.g. "serialdata[1].port"
+    source: ChangeSource,
+}
+
+type Listener = Box<dyn Fn(&Config, &[String]) + Send + Sync>;
+
+struct Subscriber {
+    keys: Vec<String>,
+    listener: Listener,
+}
+
+// "serialdata" matches "serialdata", "serialdata[1].port" and so on
+fn path_matches(path: &str, key: &str) -> bool {
+    path.strip_prefix(key)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.') || rest.starts_with('['))
+}
+
+fn top_level_key(path: &str) -> &str {
+    path.split(['.', '[']).next().unwrap_or(path)
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// The parsed configuration, loaded once and shared as managed state.
+/// Reads are served from memory; updates are validated, written to disk and
+/// then passed to the subscribers of the changed settings. Edits made to the
+/// file outside the app are picked up the same way.
+pub struct ConfigStore {
+    config: RwLock<Config>,
+    subscribers: Mutex<Vec<Subscriber>>,
+    path: PathBuf,
+    known_modified: Mutex<Option<SystemTime>>, // File time of the last load or save
+    app_handle: AppHandle,
+    logger: Arc<Logger>,
+}
+
+impl ConfigStore {
+    pub fn start(
+        app_handle: AppHandle,
+        logger: Arc<Logger>,
+    ) -> Result<Arc<Self>, Box<dyn std::error::Error>> {
+        let config = read_config_file(Arc::clone(&logger))?;
+        let path = ensure_config_exists(Arc::clone(&logger))?;
+        let store = Arc::new(ConfigStore {
+            config: RwLock::new(config),
+            subscribers: Mutex::new(Vec::new()),
+            known_modified: Mutex::new(modified_time(&path)),
+            path,
+            app_handle,
+            logger,
+        });
+
+        let watcher = Arc::downgrade(&store);
+        thread::spawn(move || loop {
+            thread::sleep(WATCH_INTERVAL);
+            match watcher.upgrade() {
+                Some(store) => store.check_file(),
+                None => break,
+            }
+        });
+
+        Ok(store)
+    }
+
+    /// A copy of the current configuration
+    pub fn get(&self) -> Config {
+        self.read_config().clone()
+    }
+
+    /// Look at the current configuration without copying it
+    pub fn with<T>(&self, read: impl FnOnce(&Config) -> T) -> T {
+        read(&self.read_config())
+    }
+
+    /// Apply a JSON merge patch, save the result and notify subscribers
+    pub fn update(&self, patch: &Value) -> Result<ConfigUpdate, String> {
+        let mut config = self.write_config();
+        let update = patch_config(&config, patch).map_err(|message| {
+            self.logger
+                .log_error(
+                    &format!("Rejected config patch: {}", message),
+                    file!(),
+                    "update",
+                    line!(),
+                )
+                .ok();
+            message
+        })?;
+
+        if !update.changed.is_empty() {
+            save_config(&self.logger, &update.config).map_err(|e| e.to_string())?;
+            *self.lock_known_modified() = modified_time(&self.path);
+            *config = update.config.clone();
+        }
+        drop(config);
+
+        self.logger
+            .log(
+                LogLevel::INFO,
+                &format!("Config updated, changed: [{}]", update.changed.join(", ")),
+            )
+            .ok();
+        self.notify(&update, ChangeSource::App);
+        Ok(update)
+    }
+
+    /// Set one top-level key from its string form, as sent by older frontends
+    pub fn update_key(&self, key: &str, value: &str) -> Result<ConfigUpdate, String> {
+        self.update(&key_patch(key, value)?)
+    }
+
+    /// Call `listener` after an update that changed any of `keys`,
+    /// with the new config and the changed paths under those keys
+    pub fn subscribe(
+        &self,
+        keys: &[&str],
+        listener: impl Fn(&Config, &[String]) + Send + Sync + 'static,
+    ) {
+        self.lock_subscribers().push(Subscriber {
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+            listener: Box::new(listener),
+        });
+    }
+
+    // Reload the file if it was written by something other than this store.
+    // The file replaces the whole config, so removed settings go back to their
+    // defaults; an invalid or half-written file is ignored until it is fixed.
+    fn check_file(&self) {
+        let modified = modified_time(&self.path);
+        if modified.is_none() || modified == *self.lock_known_modified() {
+            return;
+        }
+        *self.lock_known_modified() = modified;
+
+        let result = fs::read_to_string(&self.path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                let mut config = self.write_config();
+                let update = reload_config(&config, &content)?;
+                *config = update.config.clone();
+                Ok(update)
+            });
+
+        match result {
+            Ok(update) if update.changed.is_empty() => {}
+            Ok(update) => {
+                self.logger
+                    .log(
+                        LogLevel::INFO,
+                        &format!(
+                            "Config file changed on disk, reloaded: [{}]",
+                            update.changed.join(", ")
+                        ),
+                    )
+                    .ok();
+                self.notify(&update, ChangeSource::File);
+            }
+            Err(e) => {
+                self.logger
+                    .log(
+                        LogLevel::WARN,
+                        &format!("Ignoring config file change: {}", e),
+                    )
+                    .ok();
+            }
+        }
+    }
+
+    fn notify(&self, update: &ConfigUpdate, source: ChangeSource) {
+        if update.changed.is_empty() {
+            return;
+        }
+
+        let mut keys: Vec<String> = Vec::new();
+        for path in &update.changed {
+            let key = top_level_key(path);
+            if !keys.iter().any(|existing| existing == key) {
+                keys.push(key.to_string());
+            }
+        }
+        let changed = ConfigChanged {
+            keys,
+            paths: update.changed.clone(),
+            source,
+        };
+        if let Err(e) = self.app_handle.emit("config-changed", changed) {
+            self.logger
+                .log(
+                    LogLevel::WARN,
+                    &format!("Failed to emit config-changed event: {}", e),
+                )
+                .ok();
+        }
+
+        for subscriber in self.lock_subscribers().iter() {
+            let changed: Vec<String> = update
+                .changed
+                .iter()
+                .filter(|path| subscriber.keys.iter().any(|key| path_matches(path, key)))
+                .cloned()
+                .collect();
+            if !changed.is_empty() {
+                (subscriber.listener)(&update.config, &changed);
+            }
+        }
+    }
+
+    fn read_config(&self) -> RwLockReadGuard<'_, Config> {
+        match self.config.read() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn write_config(&self) -> RwLockWriteGuard<'_, Config> {
+        match self.config.write() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn lock_known_modified(&self) -> std::sync::MutexGuard<'_, Option<SystemTime>> {
+        match self.known_modified.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn lock_subscribers(&self) -> std::sync::MutexGuard<'_, Vec<Subscriber>> {
+        match self.subscribers.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
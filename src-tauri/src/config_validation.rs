@@ -0,0 +1,438 @@
+use crate::presence::parse_schedule_time;
+use crate::sensor::parse_pattern;
+use crate::store::{Config, HumanSensorSettings, PresenceDebounce, SensorSource};
+use reqwest::Url;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+
+const SUPPORTED_BAUD_RATES: &[u32] = &[1200, 2400, 4800, 9600, 19200, 38400, 57600, 115200];
+const SUPPORTED_DATA_BITS: &[u32] = &[5, 6, 7, 8];
+const SUPPORTED_STOP_BITS: &[u32] = &[1, 2];
+const SUPPORTED_PARITY: &[u32] = &[0, 1, 2]; // None, odd, even
+const TIMER_RANGE_SECS: (u32, u32) = (1, 3600);
+const SUPPORTED_PAPER_WIDTHS_MM: &[u32] = &[58, 80];
+const SUPPORTED_PRINTER_DPI: &[u32] = &[180, 203];
+const FONT_A_DOTS: u32 = 12; // Width of one font A character
+const OPTIONAL_URL_KEYS: &[&str] = &["manager_ip_url"];
+
+/// One problem found by `validate_config`, e.g. field "serialdata[2].baudrate"
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ConfigError {
+    pub field: String,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Join errors into the message returned when a config write is rejected
+pub fn describe_errors(errors: &[ConfigError]) -> String {
+    let details: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+    format!("Invalid configuration: {}", details.join("; "))
+}
+
+struct Errors(Vec<ConfigError>);
+
+impl Errors {
+    fn push(&mut self, field: impl Into<String>, code: &'static str, message: impl Into<String>) {
+        self.0.push(ConfigError {
+            field: field.into(),
+            code,
+            message: message.into(),
+        });
+    }
+}
+
+fn check_url(errors: &mut Errors, field: &str, value: &str) {
+    if value.trim().is_empty() {
+        if !OPTIONAL_URL_KEYS.contains(&field) {
+            errors.push(field, "required", "URL is required");
+        }
+        return;
+    }
+    match Url::parse(value) {
+        Ok(url) if url.scheme() != "http" && url.scheme() != "https" => errors.push(
+            field,
+            "unsupported_scheme",
+            format!("URL scheme must be http or https, not {}", url.scheme()),
+        ),
+        Ok(url) if url.host_str().is_none() => errors.push(field, "invalid_url", "URL has no host"),
+        Ok(_) => {}
+        Err(e) => errors.push(field, "invalid_url", format!("Invalid URL: {}", e)),
+    }
+}
+
+fn check_debounce(errors: &mut Errors, field: &str, debounce: &PresenceDebounce) {
+    if debounce.on_level > 100 || debounce.off_level >= debounce.on_level {
+        errors.push(
+            format!("{}.on_level", field),
+            "invalid_range",
+            "Levels must satisfy off_level < on_level <= 100",
+        );
+    }
+}
+
+fn check_sensor(errors: &mut Errors, field: &str, sensor: &HumanSensorSettings) {
+    match &sensor.source {
+        SensorSource::SerialFrame {
+            present_pattern,
+            absent_pattern,
+            ..
+        } => {
+            if let Err(e) = parse_pattern(present_pattern) {
+                errors.push(
+                    format!("{}.source.present_pattern", field),
+                    "invalid_pattern",
+                    e,
+                );
+            }
+            if let Some(Err(e)) = absent_pattern.as_deref().map(parse_pattern) {
+                errors.push(
+                    format!("{}.source.absent_pattern", field),
+                    "invalid_pattern",
+                    e,
+                );
+            }
+        }
+        SensorSource::SysfsGpio { path, .. } if path.trim().is_empty() => {
+            errors.push(
+                format!("{}.source.path", field),
+                "required",
+                "GPIO path is required",
+            );
+        }
+        SensorSource::GpioChip { chip, .. } if chip.trim().is_empty() => {
+            errors.push(
+                format!("{}.source.chip", field),
+                "required",
+                "GPIO chip is required",
+            );
+        }
+        _ => {}
+    }
+    if sensor.source == SensorSource::ModemPins && sensor.inputs.is_empty() {
+        errors.push(
+            format!("{}.inputs", field),
+            "required",
+            "At least one input pin is required",
+        );
+    }
+
+    check_debounce(errors, &format!("{}.debounce", field), &sensor.debounce);
+    for (index, window) in sensor.schedule.iter().enumerate() {
+        let window_field = format!("{}.schedule[{}]", field, index);
+        for (name, value) in [("start", &window.start), ("end", &window.end)] {
+            if parse_schedule_time(value).is_none() {
+                errors.push(
+                    format!("{}.{}", window_field, name),
+                    "invalid_time",
+                    format!("Expected HH:MM, got \"{}\"", value),
+                );
+            }
+        }
+        if let Some(debounce) = &window.debounce {
+            check_debounce(errors, &format!("{}.debounce", window_field), debounce);
+        }
+    }
+}
+
+/// Check a configuration before it is written; an empty list means it is valid
+pub fn validate_config(config: &Config) -> Vec<ConfigError> {
+    let mut errors = Errors(Vec::new());
+
+    if config.machineId.trim().is_empty() {
+        errors.push("machineId", "required", "Machine ID is required");
+    }
+    for (field, value) in [
+        ("primary_server_url", &config.primary_server_url),
+        ("secondary_server_url", &config.secondary_server_url),
+        ("qr_server_url", &config.qr_server_url),
+        ("rfid_server_url", &config.rfid_server_url),
+        ("manager_ip_url", &config.manager_ip_url),
+    ] {
+        check_url(&mut errors, field, value);
+    }
+
+    let mut ids = HashSet::new();
+    let mut ports = HashSet::new();
+    for (index, entry) in config.serialdata.iter().enumerate() {
+        let field = format!("serialdata[{}]", index);
+        if !ids.insert(entry.ID) {
+            errors.push(
+                format!("{}.ID", field),
+                "duplicate_id",
+                format!("ID {} is used by more than one device", entry.ID),
+            );
+        }
+
+        // Devices without a port are not connected, so their line settings don't matter
+        let port = entry.port.trim();
+        if !port.is_empty() {
+            if !ports.insert(port.to_uppercase()) {
+                errors.push(
+                    format!("{}.port", field),
+                    "duplicate_port",
+                    format!("Port {} is used by more than one device", port),
+                );
+            }
+            if !SUPPORTED_BAUD_RATES.contains(&entry.baudrate) {
+                errors.push(
+                    format!("{}.baudrate", field),
+                    "unsupported_baud_rate",
+                    format!("Baud rate {} is not supported", entry.baudrate),
+                );
+            }
+            if !SUPPORTED_DATA_BITS.contains(&entry.databit) {
+                errors.push(
+                    format!("{}.databit", field),
+                    "unsupported_data_bits",
+                    format!("Data bits must be 5 to 8, not {}", entry.databit),
+                );
+            }
+            if !SUPPORTED_STOP_BITS.contains(&entry.stopbit) {
+                errors.push(
+                    format!("{}.stopbit", field),
+                    "unsupported_stop_bits",
+                    format!("Stop bits must be 1 or 2, not {}", entry.stopbit),
+                );
+            }
+            if !SUPPORTED_PARITY.contains(&entry.parity) {
+                errors.push(
+                    format!("{}.parity", field),
+                    "unsupported_parity",
+                    format!(
+                        "Parity must be 0 (none), 1 (odd) or 2 (even), not {}",
+                        entry.parity
+                    ),
+                );
+            }
+        }
+
+        if let Some(profile) = &entry.profile {
+            if !config
+                .printer_profiles
+                .iter()
+                .any(|candidate| &candidate.name == profile)
+            {
+                errors.push(
+                    format!("{}.profile", field),
+                    "unknown_profile",
+                    format!("No printer profile named {}", profile),
+                );
+            }
+        }
+        if let Some(sensor) = &entry.sensor {
+            check_sensor(&mut errors, &format!("{}.sensor", field), sensor);
+        }
+    }
+
+    let mut timer_ids = HashSet::new();
+    for (index, timer) in config.popup_timers.iter().enumerate() {
+        let field = format!("popup_timers[{}]", index);
+        if !timer_ids.insert(timer.ID) {
+            errors.push(
+                format!("{}.ID", field),
+                "duplicate_id",
+                format!("Timer ID {} is used more than once", timer.ID),
+            );
+        }
+        if let Some(time) = timer.time {
+            let (min, max) = TIMER_RANGE_SECS;
+            if time < min || time > max {
+                errors.push(
+                    format!("{}.time", field),
+                    "out_of_range",
+                    format!("{} must be between {} and {} seconds", timer.name, min, max),
+                );
+            }
+        }
+    }
+
+    let mut profile_names = HashSet::new();
+    for (index, profile) in config.printer_profiles.iter().enumerate() {
+        let field = format!("printer_profiles[{}]", index);
+        if !profile_names.insert(profile.name.as_str()) {
+            errors.push(
+                format!("{}.name", field),
+                "duplicate_name",
+                format!("Printer profile {} is defined more than once", profile.name),
+            );
+        }
+        if !SUPPORTED_PAPER_WIDTHS_MM.contains(&profile.paper_width_mm) {
+            errors.push(
+                format!("{}.paper_width_mm", field),
+                "unsupported_paper_width",
+                format!(
+                    "Paper width must be 58 or 80 mm, not {}",
+                    profile.paper_width_mm
+                ),
+            );
+        }
+        if !SUPPORTED_PRINTER_DPI.contains(&profile.dpi) {
+            errors.push(
+                format!("{}.dpi", field),
+                "unsupported_dpi",
+                format!("Resolution must be 180 or 203 dpi, not {}", profile.dpi),
+            );
+        }
+        let print_dots = profile.paper_width_mm * profile.dpi * 10 / 254;
+        if profile.chars_per_line == 0 {
+            errors.push(
+                format!("{}.chars_per_line", field),
+                "out_of_range",
+                "Characters per line must be greater than 0",
+            );
+        } else if profile.chars_per_line * FONT_A_DOTS > print_dots {
+            errors.push(
+                format!("{}.chars_per_line", field),
+                "out_of_range",
+                format!(
+                    "{} characters do not fit on {} mm paper",
+                    profile.chars_per_line, profile.paper_width_mm
+                ),
+            );
+        }
+        if profile.write_chunk_bytes == 0 {
+            errors.push(
+                format!("{}.write_chunk_bytes", field),
+                "out_of_range",
+                "Write chunk size must be greater than 0",
+            );
+        }
+    }
+
+    errors.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::default_config;
+
+    // Field and code of every error, in the order they were found
+    fn problems(config: &Config) -> Vec<(String, &'static str)> {
+        validate_config(config)
+            .into_iter()
+            .map(|error| (error.field, error.code))
+            .collect()
+    }
+
+    fn problem(field: &str, code: &'static str) -> (String, &'static str) {
+        (field.to_string(), code)
+    }
+
+    // Default config with the printer and RFID reader connected
+    fn connected_config() -> Config {
+        let mut config = default_config();
+        for (index, port) in ["COM1", "COM2"].iter().enumerate() {
+            config.serialdata[index].port = port.to_string();
+            config.serialdata[index].baudrate = 9600;
+        }
+        config
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        assert_eq!(problems(&default_config()), vec![]);
+        assert_eq!(problems(&connected_config()), vec![]);
+    }
+
+    #[test]
+    fn rejects_bad_urls() {
+        let mut config = default_config();
+        config.primary_server_url = "ftp://host/booking".to_string();
+        config.qr_server_url = "not a url".to_string();
+        config.rfid_server_url = String::new();
+        config.manager_ip_url = String::new(); // Optional
+        assert_eq!(
+            problems(&config),
+            vec![
+                problem("primary_server_url", "unsupported_scheme"),
+                problem("qr_server_url", "invalid_url"),
+                problem("rfid_server_url", "required"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_ids_and_ports() {
+        let mut config = connected_config();
+        config.serialdata[1].ID = config.serialdata[0].ID;
+        config.serialdata[1].port = "com1".to_string();
+        assert_eq!(
+            problems(&config),
+            vec![
+                problem("serialdata[1].ID", "duplicate_id"),
+                problem("serialdata[1].port", "duplicate_port"),
+            ]
+        );
+
+        let mut config = default_config();
+        config.popup_timers[1].ID = config.popup_timers[0].ID;
+        assert_eq!(
+            problems(&config),
+            vec![problem("popup_timers[1].ID", "duplicate_id")]
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_line_settings_on_connected_ports_only() {
+        let mut config = connected_config();
+        config.serialdata[0].baudrate = 14400;
+        config.serialdata[0].parity = 3;
+        config.serialdata[1].databit = 9;
+        config.serialdata[1].stopbit = 0;
+        // Not connected, so its line settings are ignored
+        config.serialdata[2].port = String::new();
+        config.serialdata[2].baudrate = 1;
+        assert_eq!(
+            problems(&config),
+            vec![
+                problem("serialdata[0].baudrate", "unsupported_baud_rate"),
+                problem("serialdata[0].parity", "unsupported_parity"),
+                problem("serialdata[1].databit", "unsupported_data_bits"),
+                problem("serialdata[1].stopbit", "unsupported_stop_bits"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_timers_out_of_range() {
+        let mut config = default_config();
+        config.popup_timers[0].time = Some(0);
+        config.popup_timers[1].time = Some(3601);
+        config.popup_timers[2].time = Some(3600);
+        assert_eq!(
+            problems(&config),
+            vec![
+                problem("popup_timers[0].time", "out_of_range"),
+                problem("popup_timers[1].time", "out_of_range"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_printer_profiles() {
+        let mut config = default_config();
+        config.printer_profiles[0].paper_width_mm = 76;
+        config.printer_profiles[0].dpi = 0;
+        config.printer_profiles[1].chars_per_line = 42; // Wider than 58 mm paper
+        assert_eq!(
+            problems(&config),
+            vec![
+                problem(
+                    "printer_profiles[0].paper_width_mm",
+                    "unsupported_paper_width"
+                ),
+                problem("printer_profiles[0].dpi", "unsupported_dpi"),
+                problem("printer_profiles[0].chars_per_line", "out_of_range"),
+                problem("printer_profiles[1].chars_per_line", "out_of_range"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,370 @@
+use crate::config_store::ConfigStore;
+use crate::logger::{LogLevel, Logger};
+use crate::occupancy::OccupancyLog;
+use crate::store::Config;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+const LOG_OUT_TIMER: &str = "LOG OUT TIMER";
+const LOG_OUT_FLOOR_TIMER: &str = "LOG OUT FLOOR TIMER";
+const SESSION_REMINDER_TIMER: &str = "SESSION TIMER REMINDER";
+const RESET_ON_TOUCH_TIMER: &str = "RESET TIMER ON TOUCH";
+
+/// Screen a user session is on; each has its own logout timer
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdleScreen {
+    Main,
+    Floor,
+}
+
+/// Payload of the `session-expire-warning` event
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionExpireWarning {
+    screen: IdleScreen,
+    remaining_secs: u64,
+}
+
+/// Payload of the `session-expired` event
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionExpired {
+    screen: IdleScreen,
+    person_present: Option<bool>, // Whether the sensor saw someone at the kiosk when it expired
+}
+
+/// Payload of the `attract-mode` event
+#[derive(Debug, Clone, Serialize)]
+pub struct AttractMode {
+    active: bool,
+}
+
+enum IdleEvent {
+    Warning(SessionExpireWarning),
+    Expired(SessionExpired),
+    Attract(AttractMode),
+}
+
+struct IdleSession {
+    screen: IdleScreen,
+    timeout: Option<Duration>, // None when the screen's logout timer is disabled
+    remaining: Duration,
+    warning: Duration,
+    warned: bool,
+    reset_on_activity: bool,
+}
+
+struct IdleState {
+    session: Option<IdleSession>,
+    sensor_enabled: bool,
+    sensor_paused: bool, // Switched off by the human sensor schedule
+    attract_after: Option<Duration>,
+    present: Option<bool>, // None until the sensor reports
+    absent_since: Instant,
+    attract: bool,
+    last_tick: Instant,
+}
+
+/// Logs users out and starts attract mode based on presence and the logout popup timers.
+/// The logout countdown only runs while nobody is at the kiosk (or always, when human
+/// sensor detection is off); a returning person or any touch restarts it.
+pub struct IdleController {
+    state: Mutex<IdleState>,
+    config: Arc<ConfigStore>,
+    app_handle: AppHandle,
+    logger: Arc<Logger>,
+}
+
+fn timer(config: &Config, name: &str) -> Option<Duration> {
+    config
+        .popup_timers
+        .iter()
+        .find(|timer| timer.name == name && timer.state)
+        .and_then(|timer| timer.time)
+        .map(|secs| Duration::from_secs(secs as u64))
+}
+
+impl IdleController {
+    pub fn start(
+        app_handle: AppHandle,
+        config_store: &Arc<ConfigStore>,
+        logger: Arc<Logger>,
+    ) -> Result<Arc<Self>, Box<dyn std::error::Error>> {
+        let config = config_store.get();
+        let now = Instant::now();
+
+        let controller = Arc::new(IdleController {
+            state: Mutex::new(IdleState {
+                session: None,
+                sensor_enabled: config.humanSensorDetection,
+                sensor_paused: false,
+                attract_after: Some(Duration::from_secs(config.idle.attract_after_secs))
+                    .filter(|after| !after.is_zero()),
+                present: None,
+                absent_since: now,
+                attract: false,
+                last_tick: now,
+            }),
+            config: Arc::clone(config_store),
+            app_handle,
+            logger,
+        });
+
+        // Pick up sensor and attract mode settings without waiting for the next session
+        let subscriber = Arc::downgrade(&controller);
+        config_store.subscribe(&["humanSensorDetection", "idle"], move |config, _| {
+            if let Some(controller) = subscriber.upgrade() {
+                let mut state = controller.lock_state();
+                state.sensor_enabled = config.humanSensorDetection;
+                state.attract_after = Some(Duration::from_secs(config.idle.attract_after_secs))
+                    .filter(|after| !after.is_zero());
+            }
+        });
+
+        let ticker = Arc::clone(&controller);
+        thread::spawn(move || loop {
+            thread::sleep(TICK_INTERVAL);
+            ticker.tick();
+        });
+
+        Ok(controller)
+    }
+
+    /// A user logged in or moved to another screen
+    pub fn start_session(&self, screen: IdleScreen) -> Result<(), String> {
+        let config = self.config.get();
+        let timeout = timer(
+            &config,
+            match screen {
+                IdleScreen::Main => LOG_OUT_TIMER,
+                IdleScreen::Floor => LOG_OUT_FLOOR_TIMER,
+            },
+        );
+        let reset_on_activity = config
+            .popup_timers
+            .iter()
+            .find(|timer| timer.name == RESET_ON_TOUCH_TIMER)
+            .is_none_or(|timer| timer.state);
+
+        let mut events = Vec::new();
+        let login;
+        {
+            let mut state = self.lock_state();
+            login = state.session.is_none();
+            state.sensor_enabled = config.humanSensorDetection;
+            state.attract_after = Some(Duration::from_secs(config.idle.attract_after_secs))
+                .filter(|after| !after.is_zero());
+            state.session = Some(IdleSession {
+                screen,
+                timeout,
+                remaining: timeout.unwrap_or_default(),
+                warning: timer(&config, SESSION_REMINDER_TIMER).unwrap_or_default(),
+                warned: false,
+                reset_on_activity,
+            });
+            Self::leave_attract(&mut state, &mut events);
+        }
+        self.emit(events);
+
+        // Moving between screens keeps the session; only a new one is a login
+        if login {
+            if let Some(occupancy) = self.app_handle.try_state::<Arc<OccupancyLog>>() {
+                occupancy.record_login();
+            }
+        }
+
+        self.logger
+            .log(
+                LogLevel::INFO,
+                &format!(
+                    "Idle session started on {:?} screen, timeout {:?}",
+                    screen, timeout
+                ),
+            )
+            .ok();
+        Ok(())
+    }
+
+    /// The user logged out
+    pub fn end_session(&self) {
+        let mut state = self.lock_state();
+        state.session = None;
+        state.absent_since = Instant::now();
+    }
+
+    /// The user touched the screen or pressed a key
+    pub fn activity(&self) {
+        let mut events = Vec::new();
+        {
+            let mut state = self.lock_state();
+            if let Some(session) = state.session.as_mut().filter(|s| s.reset_on_activity) {
+                Self::restart_countdown(session);
+            }
+            state.absent_since = Instant::now();
+            Self::leave_attract(&mut state, &mut events);
+        }
+        self.emit(events);
+    }
+
+    /// Debounced presence change from the human sensor thread
+    pub fn on_presence(&self, present: bool) {
+        let mut events = Vec::new();
+        {
+            let mut state = self.lock_state();
+            if !state.sensor_enabled || state.sensor_paused {
+                return;
+            }
+            state.present = Some(present);
+            if present {
+                if let Some(session) = state.session.as_mut() {
+                    Self::restart_countdown(session);
+                }
+                Self::leave_attract(&mut state, &mut events);
+            } else {
+                state.absent_since = Instant::now();
+            }
+        }
+        self.emit(events);
+    }
+
+    /// The human sensor schedule switched detection on or off
+    pub fn on_sensor_active(&self, active: bool) {
+        let mut state = self.lock_state();
+        state.sensor_paused = !active;
+        if !active {
+            // Count down and hold off attract mode as if there were no sensor
+            state.present = None;
+        }
+    }
+
+    fn tick(&self) {
+        let mut events = Vec::new();
+        {
+            let mut state = self.lock_state();
+            let now = Instant::now();
+            let elapsed = now.saturating_duration_since(state.last_tick);
+            state.last_tick = now;
+
+            let counting = !(state.sensor_enabled && state.present == Some(true));
+            let present = state.present;
+            let mut expired = false;
+            if let Some(session) = state.session.as_mut().filter(|s| s.timeout.is_some()) {
+                if counting {
+                    session.remaining = session.remaining.saturating_sub(elapsed);
+                }
+                if session.remaining.is_zero() {
+                    expired = true;
+                    events.push(IdleEvent::Expired(SessionExpired {
+                        screen: session.screen,
+                        person_present: present,
+                    }));
+                } else if counting && !session.warned && session.remaining <= session.warning {
+                    session.warned = true;
+                    events.push(IdleEvent::Warning(SessionExpireWarning {
+                        screen: session.screen,
+                        remaining_secs: session.remaining.as_secs(),
+                    }));
+                }
+            }
+            if expired {
+                state.session = None;
+                state.absent_since = now;
+            }
+
+            let nobody_for = now.saturating_duration_since(state.absent_since);
+            if let Some(after) = state.attract_after {
+                if state.sensor_enabled
+                    && !state.attract
+                    && state.session.is_none()
+                    && state.present == Some(false)
+                    && nobody_for >= after
+                {
+                    state.attract = true;
+                    events.push(IdleEvent::Attract(AttractMode { active: true }));
+                }
+            }
+        }
+        self.emit(events);
+    }
+
+    fn restart_countdown(session: &mut IdleSession) {
+        if let Some(timeout) = session.timeout {
+            session.remaining = timeout;
+            session.warned = false;
+        }
+    }
+
+    fn leave_attract(state: &mut IdleState, events: &mut Vec<IdleEvent>) {
+        if state.attract {
+            state.attract = false;
+            events.push(IdleEvent::Attract(AttractMode { active: false }));
+        }
+    }
+
+    fn emit(&self, events: Vec<IdleEvent>) {
+        for event in events {
+            let result = match event {
+                IdleEvent::Warning(warning) => {
+                    self.logger
+                        .log(
+                            LogLevel::INFO,
+                            &format!("Session expires in {} s", warning.remaining_secs),
+                        )
+                        .ok();
+                    self.app_handle.emit("session-expire-warning", warning)
+                }
+                IdleEvent::Expired(expired) => {
+                    self.logger
+                        .log(
+                            LogLevel::INFO,
+                            &format!("Session on {:?} screen expired", expired.screen),
+                        )
+                        .ok();
+                    self.app_handle.emit("session-expired", expired)
+                }
+                IdleEvent::Attract(attract) => {
+                    self.logger
+                        .log(
+                            LogLevel::INFO,
+                            &format!("Attract mode {}", if attract.active { "on" } else { "off" }),
+                        )
+                        .ok();
+                    self.app_handle.emit("attract-mode", attract)
+                }
+            };
+            if let Err(e) = result {
+                println!("Failed to emit idle event: {}", e);
+            }
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, IdleState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn start_idle_session(
+    screen: IdleScreen,
+    idle: State<'_, Arc<IdleController>>,
+) -> Result<(), String> {
+    idle.start_session(screen)
+}
+
+#[tauri::command]
+pub fn end_idle_session(idle: State<'_, Arc<IdleController>>) -> Result<(), String> {
+    idle.end_session();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn report_user_activity(idle: State<'_, Arc<IdleController>>) -> Result<(), String> {
+    idle.activity();
+    Ok(())
+}
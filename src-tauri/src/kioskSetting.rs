@@ -1,5 +1,6 @@
+use crate::config_store::ConfigStore;
 use crate::logger::{LogLevel, Logger};
-use crate::store::read_config_file;
+use crate::store::Config;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -13,15 +14,15 @@ pub struct ApiResponse {
     pub msg: String,
 }
 
-const RELEASE_VERSION: &str = "1.1.3";
+pub const RELEASE_VERSION: &str = "1.1.3";
 
 static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(reqwest::Client::new);
 
-async fn make_kiosk_login_request(logger: Arc<Logger>) -> Result<ApiResponse, String> {
-    let config = read_config_file(Arc::clone(&logger))
-        .map_err(|e| format!("Error reading config: {}", e))?;
-
-    // Use primary_server_url which is already a complete URL
+async fn make_kiosk_login_request(
+    config: Config,
+    logger: Arc<Logger>,
+) -> Result<ApiResponse, String> {
+    // Stored without a trailing slash; tolerate hand-edited files that still have one
     let base_url = config.primary_server_url.trim_end_matches('/');
 
     let endpoint = format!(
@@ -29,7 +30,7 @@ async fn make_kiosk_login_request(logger: Arc<Logger>) -> Result<ApiResponse, St
         config.machineId, RELEASE_VERSION
     );
 
-    let api_url = format!("{}{}", base_url, endpoint);
+    let api_url = format!("{}/{}", base_url, endpoint);
 
     logger
         .log(
@@ -106,8 +107,9 @@ async fn make_kiosk_login_request(logger: Arc<Logger>) -> Result<ApiResponse, St
 pub async fn listen_kiosk_login(
     app_handle: AppHandle,
     logger: State<'_, Arc<Logger>>,
+    config: State<'_, Arc<ConfigStore>>,
 ) -> Result<(), String> {
-    match make_kiosk_login_request(Arc::clone(&logger)).await {
+    match make_kiosk_login_request(config.get(), Arc::clone(&logger)).await {
         Ok(api_data) => {
             app_handle
                 .emit("api-data-received", api_data.clone())
@@ -119,6 +121,38 @@ pub async fn listen_kiosk_login(
 }
 
 #[tauri::command]
-pub async fn request_kiosk_login(logger: State<'_, Arc<Logger>>) -> Result<ApiResponse, String> {
-    make_kiosk_login_request(Arc::clone(&logger)).await
+pub async fn request_kiosk_login(
+    logger: State<'_, Arc<Logger>>,
+    config: State<'_, Arc<ConfigStore>>,
+) -> Result<ApiResponse, String> {
+    make_kiosk_login_request(config.get(), Arc::clone(&logger)).await
+}
+
+/// Re-run the kiosk login when the server URL changes, emitting the result the
+/// same way `listen_kiosk_login` does
+pub fn relogin_on_url_change(app_handle: AppHandle, config: &ConfigStore, logger: Arc<Logger>) {
+    config.subscribe(&["primary_server_url"], move |config, _| {
+        let app_handle = app_handle.clone();
+        let logger = Arc::clone(&logger);
+        let config = config.clone();
+        tauri::async_runtime::spawn(async move {
+            match make_kiosk_login_request(config, Arc::clone(&logger)).await {
+                Ok(api_data) => {
+                    if let Err(e) = app_handle.emit("api-data-received", api_data) {
+                        println!("Failed to emit api-data-received event: {}", e);
+                    }
+                }
+                Err(e) => {
+                    logger
+                        .log_error(
+                            &format!("Kiosk login after server URL change failed: {}", e),
+                            file!(),
+                            "relogin_on_url_change",
+                            line!(),
+                        )
+                        .ok();
+                }
+            }
+        });
+    });
 }
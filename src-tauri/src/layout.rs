@@ -0,0 +1,285 @@
+use unicode_width::UnicodeWidthChar;
+
+const ELLIPSIS: &str = "...";
+
+/// Printed width of a character in columns.
+/// Hangul and other East Asian wide characters take two columns on the printer.
+pub fn char_width(c: char) -> usize {
+    c.width_cjk().unwrap_or(0)
+}
+
+/// Printed width of a string in columns
+pub fn text_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// Pad a string with spaces on the right up to `width` columns
+pub fn pad_right(text: &str, width: usize) -> String {
+    let mut padded = text.to_string();
+    padded.push_str(&" ".repeat(width.saturating_sub(text_width(text))));
+    padded
+}
+
+/// Pad a string with spaces on the left up to `width` columns
+pub fn pad_left(text: &str, width: usize) -> String {
+    let mut padded = " ".repeat(width.saturating_sub(text_width(text)));
+    padded.push_str(text);
+    padded
+}
+
+/// Cut a string to at most `width` columns, ending it with an ellipsis when it was too long
+pub fn truncate(text: &str, width: usize) -> String {
+    if text_width(text) <= width {
+        return text.to_string();
+    }
+
+    let ellipsis_width = text_width(ELLIPSIS);
+    if width < ellipsis_width {
+        return take_columns(text, width).0.to_string();
+    }
+
+    let mut truncated = take_columns(text, width - ellipsis_width).0.to_string();
+    truncated.push_str(ELLIPSIS);
+    truncated
+}
+
+/// Split off the longest prefix that fits in `width` columns, never splitting a wide character
+fn take_columns(text: &str, width: usize) -> (&str, &str) {
+    let mut used = 0;
+    for (index, c) in text.char_indices() {
+        let w = char_width(c);
+        if used + w > width {
+            return text.split_at(index);
+        }
+        used += w;
+    }
+    (text, "")
+}
+
+/// Word-wrap text to lines of at most `width` columns.
+/// Words longer than a line are broken at the column limit; existing newlines are kept.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let first_line = lines.len();
+        let mut line = String::new();
+        let mut line_width = 0;
+
+        for word in paragraph.split(' ').filter(|word| !word.is_empty()) {
+            let mut word = word;
+            let mut word_width = text_width(word);
+
+            // Start a new line if the word doesn't fit after a separating space
+            if line_width > 0 && line_width + 1 + word_width > width {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+
+            // Break words that are wider than a whole line
+            while word_width > width {
+                let (mut head, mut tail) = take_columns(word, width);
+                if head.is_empty() {
+                    // A wide character on a one-column line still has to go somewhere
+                    let first = word.chars().next().map_or(0, char::len_utf8);
+                    (head, tail) = word.split_at(first);
+                }
+                lines.push(head.to_string());
+                word = tail;
+                word_width = text_width(word);
+            }
+
+            if word.is_empty() {
+                continue;
+            }
+            if line_width > 0 {
+                line.push(' ');
+                line_width += 1;
+            }
+            line.push_str(word);
+            line_width += word_width;
+        }
+
+        // Keep blank paragraphs, but not the empty tail left by a broken word
+        if !line.is_empty() || lines.len() == first_line {
+            lines.push(line);
+        }
+    }
+
+    lines
+}
+
+/// Lay out a label on the left and a value on the right of a `width` column line.
+/// A value that doesn't fit next to the label wraps onto following lines,
+/// indented to start after the label column.
+pub fn two_column(label: &str, value: &str, width: usize) -> Vec<String> {
+    let label = truncate(label, width / 2);
+    let label_width = text_width(&label);
+
+    // Single line: label, gap, right-aligned value
+    if label_width + 1 + text_width(value) <= width {
+        return vec![format!(
+            "{}{}",
+            label,
+            pad_left(value, width - label_width)
+        )];
+    }
+
+    let value_width = width.saturating_sub(label_width + 1).max(1);
+    wrap(value, value_width)
+        .into_iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let prefix = if index == 0 {
+                pad_right(&label, label_width + 1)
+            } else {
+                " ".repeat(label_width + 1)
+            };
+            format!("{}{}", prefix, line)
+        })
+        .collect()
+}
+
+/// Lay out table rows in columns of the given widths.
+/// Cells are truncated with an ellipsis to fit and columns are separated by one space.
+/// Without explicit widths the line is split evenly between the columns.
+pub fn table(rows: &[Vec<String>], widths: Option<&[usize]>, width: usize) -> Vec<String> {
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    if columns == 0 {
+        return Vec::new();
+    }
+
+    let widths: Vec<usize> = match widths {
+        Some(widths) => widths.to_vec(),
+        None => {
+            let available = width.saturating_sub(columns - 1);
+            let even = available / columns;
+            let mut widths = vec![even; columns];
+            // Give the remainder to the first column, which usually holds the label
+            widths[0] += available - even * columns;
+            widths
+        }
+    };
+
+    rows.iter()
+        .map(|row| {
+            let cells: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(index, column_width)| {
+                    let cell = row.get(index).map(String::as_str).unwrap_or("");
+                    pad_right(&truncate(cell, *column_width), *column_width)
+                })
+                .collect();
+            truncate(cells.join(" ").trim_end(), width)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn widths(lines: &[String]) -> Vec<usize> {
+        lines.iter().map(|line| text_width(line)).collect()
+    }
+
+    #[test]
+    fn hangul_takes_two_columns() {
+        assert_eq!(char_width('a'), 1);
+        assert_eq!(char_width('좌'), 2);
+        assert_eq!(char_width('\u{0301}'), 0); // Combining accent
+        assert_eq!(text_width("서울대 SNU"), 10);
+        assert_eq!(text_width(""), 0);
+    }
+
+    #[test]
+    fn padding_counts_columns() {
+        assert_eq!(pad_right("좌석", 6), "좌석  ");
+        assert_eq!(pad_left("좌석", 6), "  좌석");
+        assert_eq!(pad_left("a좌", 6), "   a좌");
+        // Text that is already too wide is left alone
+        assert_eq!(pad_right("서울대학교", 4), "서울대학교");
+    }
+
+    #[test]
+    fn truncate_never_splits_a_wide_character() {
+        assert_eq!(truncate("서울대학교", 10), "서울대학교");
+        assert_eq!(truncate("서울대학교", 7), "서울...");
+        assert_eq!(truncate("서울대학교", 8), "서울...");
+        assert_eq!(truncate("Seoul 대학교", 9), "Seoul ...");
+        // Too narrow for the ellipsis
+        assert_eq!(truncate("abcdef", 2), "ab");
+        assert_eq!(truncate("서울", 1), "");
+    }
+
+    #[test]
+    fn wrap_breaks_between_words() {
+        assert_eq!(
+            wrap("서울대학교 중앙도서관 열람실", 12),
+            vec!["서울대학교", "중앙도서관", "열람실"]
+        );
+        assert_eq!(
+            wrap("Seat 좌석 112 is free", 10),
+            vec!["Seat 좌석", "112 is", "free"]
+        );
+        assert_eq!(wrap("a  b", 10), vec!["a b"]);
+    }
+
+    #[test]
+    fn wrap_breaks_long_words_at_the_column_limit() {
+        assert_eq!(wrap("가나다라마", 5), vec!["가나", "다라", "마"]);
+        assert_eq!(wrap("abcdefgh", 3), vec!["abc", "def", "gh"]);
+        // A wide character still goes on a line of its own when the line is one column wide
+        assert_eq!(wrap("가a", 1), vec!["가", "a"]);
+    }
+
+    #[test]
+    fn wrap_keeps_newlines_and_blank_lines() {
+        assert_eq!(wrap("a\n\nb", 5), vec!["a", "", "b"]);
+        assert_eq!(wrap("", 5), vec![""]);
+    }
+
+    #[test]
+    fn two_column_right_aligns_values_that_fit() {
+        let lines = two_column("좌석", "112", 12);
+        assert_eq!(lines, vec!["좌석     112"]);
+        assert_eq!(widths(&lines), vec![12]);
+
+        let lines = two_column("입실 시간", "2026년 10월 19일", 30);
+        assert_eq!(lines, vec!["입실 시간     2026년 10월 19일"]);
+        assert_eq!(widths(&lines), vec![30]);
+    }
+
+    #[test]
+    fn two_column_wraps_long_values_under_the_value_column() {
+        assert_eq!(
+            two_column("열람실", "관정관 제1 자유열람실", 20),
+            vec!["열람실 관정관 제1", "       자유열람실"]
+        );
+        // The label gets at most half the line
+        let lines = two_column("아주아주긴라벨입니다", "값", 20);
+        assert_eq!(lines, vec!["아주아...         값"]);
+        assert_eq!(widths(&lines), vec![20]);
+    }
+
+    #[test]
+    fn table_pads_and_truncates_cells_by_columns() {
+        let rows = vec![
+            vec!["좌석".to_string(), "112".to_string()],
+            vec!["시간".to_string(), "09:00 - 18:00".to_string()],
+            vec!["비고".to_string()],
+        ];
+        // 19 columns left after the separator: 10 for the first column, 9 for the second
+        assert_eq!(
+            table(&rows, None, 20),
+            vec!["좌석       112", "시간       09:00 ...", "비고"]
+        );
+        assert_eq!(
+            table(&rows, Some(&[4, 15]), 20),
+            vec!["좌석 112", "시간 09:00 - 18:00", "비고"]
+        );
+        assert!(table(&[], None, 20).is_empty());
+    }
+}
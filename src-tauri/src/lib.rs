@@ -1,8 +1,16 @@
 use serde::{Deserialize, Serialize};
 use std::sync::Arc; // Import Arc
 use tauri::State;
+mod booking_slip;
+mod config_store;
+mod config_validation;
+mod idle;
 mod kioskSetting;
+mod layout;
 mod logger;
+mod occupancy;
+mod paper;
+mod paths;
 mod store; // Import the new file
 use logger::{LogLevel, Logger};
 use tauri::{
@@ -10,10 +18,31 @@ use tauri::{
     tray::TrayIconBuilder,
     Emitter,
 };
+mod presence;
+mod preview;
+mod print_history;
+mod printer;
+mod receipt_template;
+mod sensor;
 mod serialport;
+mod spooler;
+mod test_page;
+mod transport;
+use booking_slip::print_booking_slip;
+use config_store::ConfigStore;
+use idle::{end_idle_session, report_user_activity, start_idle_session, IdleController};
 use kioskSetting::{listen_kiosk_login, request_kiosk_login};
-use serialport::{continuous_read, get_serial_health, list_serial_ports, print_with_options, 
-                 start_human_sensor_monitoring, stop_human_sensor_monitoring, stop_serial_reading};
+use occupancy::{get_occupancy_stats, OccupancyLog};
+use paper::{get_paper_status, reset_paper_roll, PaperTracker};
+use preview::render_print_preview;
+use print_history::{list_print_history, reprint, PrintHistory};
+use printer::print_with_options;
+use receipt_template::{list_print_templates, print_template};
+use serialport::{continuous_read, get_serial_health, list_serial_ports,
+                 start_human_sensor_monitoring, stop_human_sensor_monitoring, stop_serial_reading,
+                 get_human_sensor_status};
+use spooler::{cancel_print_job, list_print_jobs, submit_print_job, PrintSpooler};
+use test_page::print_test_page;
 use tauri::Manager; // Bring the command into scope
 
 // Structure to accept log inputs from frontend
@@ -59,17 +88,39 @@ async fn get_app_info(logger: State<'_, Arc<Logger>>) -> Result<AppInfo, String>
 
 // Command for reading config
 #[tauri::command]
-async fn read_config(logger: State<'_, Arc<Logger>>) -> Result<store::Config, String> {
+async fn read_config(
+    logger: State<'_, Arc<Logger>>,
+    config: State<'_, Arc<ConfigStore>>,
+) -> Result<store::Config, String> {
     logger
-        .log(LogLevel::INFO, "Read config command invoked from frontend")
+        .log(LogLevel::DEBUG, "Read config command invoked from frontend")
         .unwrap();
-    store::read_config_file(Arc::clone(&logger)).map_err(|e| format!("Error reading config: {}", e))
+    Ok(config.get())
 }
 
-// Command for updating config
+// Command for applying a JSON merge patch to the config
 #[tauri::command]
 async fn update_config(
     logger: State<'_, Arc<Logger>>,
+    config: State<'_, Arc<ConfigStore>>,
+    patch: serde_json::Value,
+) -> Result<store::ConfigUpdate, String> {
+    logger
+        .log(
+            LogLevel::INFO,
+            &format!("Update config command invoked: {}", patch),
+        )
+        .unwrap();
+    config
+        .update(&patch)
+        .map_err(|e| format!("Error updating config: {}", e))
+}
+
+// Command for updating a single config key
+#[tauri::command]
+async fn update_config_key(
+    logger: State<'_, Arc<Logger>>,
+    config: State<'_, Arc<ConfigStore>>,
     key: String,
     value: String,
 ) -> Result<(), String> {
@@ -79,10 +130,25 @@ async fn update_config(
             &format!("Update config command invoked: {}={}", key, value),
         )
         .unwrap();
-    store::update_config_key(Arc::clone(&logger), &key, &value)
+    config
+        .update_key(&key, &value)
+        .map(|_| ())
         .map_err(|e| format!("Error updating config: {}", e))
 }
 
+// Command for checking a configuration before saving it; checks the saved one when none is given
+#[tauri::command]
+async fn validate_config(
+    config: Option<store::Config>,
+    config_store: State<'_, Arc<ConfigStore>>,
+) -> Result<Vec<config_validation::ConfigError>, String> {
+    let errors = match config {
+        Some(config) => config_validation::validate_config(&config),
+        None => config_store.with(config_validation::validate_config),
+    };
+    Ok(errors)
+}
+
 // Command for logging from frontend
 #[tauri::command]
 async fn log_event(logger: State<'_, Arc<Logger>>, log: LogMessage) -> Result<(), String> {
@@ -121,7 +187,23 @@ fn restart_app(app_handle: tauri::AppHandle, logger: State<'_, Arc<Logger>>) ->
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let logger = Arc::new(Logger::new().unwrap()); // Wrap the logger in Arc for shared ownership
+    let logger = match Logger::new() {
+        Ok(logger) => logger,
+        Err(e) => {
+            // Keep starting with logs in the temp folder; setup reports the data directory problem
+            let log_dir = std::env::temp_dir().join(paths::APP_DIR_NAME).join("logs");
+            eprintln!(
+                "Cannot use log directory ({}), logging to {}",
+                e,
+                log_dir.display()
+            );
+            Logger::with_dir(log_dir).expect("Failed to create a log directory")
+        }
+    };
+    let logger = Arc::new(logger); // Wrap the logger in Arc for shared ownership
+    for (level, message) in paths::take_startup_messages() {
+        logger.log(level, &message).ok();
+    }
 
     tauri::Builder::default()
         .plugin(tauri_plugin_http::init())
@@ -135,8 +217,17 @@ pub fn run() {
                     .log(LogLevel::INFO, "Application setup started")
                     .unwrap();
 
-                // Read the config file to determine if kiosk_mode is enabled
-                let config = store::read_config_file(Arc::clone(&logger_setup)).unwrap();
+                // Tell the frontend when a damaged config file had to be replaced
+                let recovery_handle = app.app_handle().clone();
+                store::on_config_recovered(move |recovery| {
+                    recovery_handle.emit("config-recovered", recovery).ok();
+                });
+
+                // Load the config once; commands and subsystems read it from the store
+                let config_store =
+                    ConfigStore::start(app.app_handle().clone(), Arc::clone(&logger_setup))?;
+                let config = config_store.get();
+                app.manage(Arc::clone(&config_store));
 
                 // Get the app handle and the webview window
                 let handle = app.app_handle();
@@ -162,6 +253,58 @@ pub fn run() {
                         .unwrap();
                 }
 
+                // Apply config changes without a restart
+                let fullscreen_window = main_window.clone();
+                let fullscreen_logger = Arc::clone(&logger_setup);
+                config_store.subscribe(&["kiosk_mode"], move |config, _| {
+                    if let Err(e) = fullscreen_window.set_fullscreen(config.kiosk_mode) {
+                        fullscreen_logger
+                            .log(LogLevel::ERROR, &format!("Failed to set fullscreen: {}", e))
+                            .ok();
+                    }
+                });
+                let serial_handle = handle.clone();
+                let serial_logger = Arc::clone(&logger_setup);
+                config_store.subscribe(
+                    &["serialdata", "humanSensorDetection"],
+                    move |config, _| {
+                        serialport::reload_serial_devices(
+                            serial_handle.clone(),
+                            Arc::clone(&serial_logger),
+                            config.clone(),
+                        )
+                    },
+                );
+                kioskSetting::relogin_on_url_change(
+                    handle.clone(),
+                    &config_store,
+                    Arc::clone(&logger_setup),
+                );
+
+                // Start the print spooler and resume any jobs left from the last run
+                let print_history = PrintHistory::start(&config_store, Arc::clone(&logger_setup))?;
+                let paper_tracker = PaperTracker::start(handle.clone(), Arc::clone(&logger_setup))?;
+                let print_spooler = PrintSpooler::start(
+                    handle.clone(),
+                    Arc::clone(&config_store),
+                    Arc::clone(&logger_setup),
+                    Arc::clone(&print_history),
+                    Arc::clone(&paper_tracker),
+                )?;
+                app.manage(print_history);
+                app.manage(paper_tracker);
+                app.manage(print_spooler);
+
+                // Presence-driven logout and attract mode, with occupancy statistics
+                let occupancy_log = OccupancyLog::start(&config_store, Arc::clone(&logger_setup))?;
+                app.manage(occupancy_log);
+                let idle_controller = IdleController::start(
+                    handle.clone(),
+                    &config_store,
+                    Arc::clone(&logger_setup),
+                )?;
+                app.manage(idle_controller);
+
                 // Menu creation
                 let about_item = MenuItem::with_id(app, "about", "About", true, None::<&str>)?;
                 let config_item =
@@ -195,21 +338,13 @@ pub fn run() {
                                 event_logger
                                     .log(LogLevel::INFO, "Configuration menu item clicked")
                                     .unwrap();
-                                match store::read_config_file(Arc::clone(&event_logger)) {
-                                    Ok(config) => event_logger
+                                if let Some(config) = app.try_state::<Arc<ConfigStore>>() {
+                                    event_logger
                                         .log(
                                             LogLevel::DEBUG,
-                                            &format!("Config loaded successfully: {:?}", config),
-                                        )
-                                        .unwrap(),
-                                    Err(e) => event_logger
-                                        .log_error(
-                                            &format!("Error reading config file: {}", e),
-                                            file!(),
-                                            "on_menu_event",
-                                            line!(),
+                                            &format!("Current config: {:?}", config.get()),
                                         )
-                                        .unwrap(),
+                                        .unwrap();
                                 }
                                 app.emit("navigate-to-config", ()).unwrap();
                             }
@@ -248,6 +383,8 @@ pub fn run() {
             greet,
             read_config,
             update_config,
+            update_config_key,
+            validate_config,
             log_event,
             list_serial_ports,
             continuous_read,
@@ -257,8 +394,25 @@ pub fn run() {
             listen_kiosk_login,
             request_kiosk_login,
             print_with_options,
+            render_print_preview,
+            submit_print_job,
+            cancel_print_job,
+            list_print_jobs,
+            list_print_history,
+            reprint,
+            get_paper_status,
+            reset_paper_roll,
+            print_test_page,
+            print_booking_slip,
+            list_print_templates,
+            print_template,
             start_human_sensor_monitoring,
             stop_human_sensor_monitoring,
+            get_human_sensor_status,
+            start_idle_session,
+            end_idle_session,
+            report_user_activity,
+            get_occupancy_stats,
             restart_app
         ])
         .run(tauri::generate_context!())
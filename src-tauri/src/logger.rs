@@ -1,6 +1,6 @@
 
+use crate::paths;
 use chrono::Local; // For timestamps and date handling
-use std::env;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::PathBuf;
@@ -30,10 +30,10 @@ pub struct Logger {
 
 impl Logger {
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let app_data_dir = env::var("APPDATA")?; // Get AppData dir (for Windows)
-        let config_dir = PathBuf::from(app_data_dir).join("wise-kiosk-app");
-        let log_dir = config_dir.join("logs");
+        Self::with_dir(paths::log_dir()?)
+    }
 
+    pub fn with_dir(log_dir: PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
         // Create log directory if it doesn't exist
         if !log_dir.exists() {
             fs::create_dir_all(&log_dir)?;
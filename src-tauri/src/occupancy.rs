@@ -0,0 +1,302 @@
+use crate::config_store::ConfigStore;
+use crate::logger::{LogLevel, Logger};
+use crate::presence::PersonLeft;
+use crate::store::get_config_dir;
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+const MAX_HOURLY_BUCKETS: i64 = 24 * 31; // Keeps an hourly query to about a month
+
+/// A finished human sensor session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresenceSession {
+    pub arrived_at: String,
+    pub left_at: String,
+    pub session_ms: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct OccupancyData {
+    #[serde(default)]
+    sessions: Vec<PresenceSession>,
+    #[serde(default)]
+    logins: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsBucket {
+    Hour,
+    Day,
+}
+
+/// Inclusive local date range, "YYYY-MM-DD"
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatsRange {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OccupancyCounts {
+    start: String,
+    approaches: u32, // Presence sessions that started in the bucket
+    logins: u32,
+    conversions: u32, // Presence sessions with a login while the person was there
+    average_dwell_ms: u64,
+}
+
+/// Result of `get_occupancy_stats`
+#[derive(Debug, Clone, Serialize)]
+pub struct OccupancyStats {
+    bucket: StatsBucket,
+    buckets: Vec<OccupancyCounts>,
+    total: OccupancyCounts,
+    conversion_rate: f64, // conversions / approaches, 0 when nobody approached
+}
+
+#[derive(Default)]
+struct Tally {
+    approaches: u32,
+    logins: u32,
+    conversions: u32,
+    dwell_ms: u64,
+}
+
+impl Tally {
+    fn counts(&self, start: String) -> OccupancyCounts {
+        OccupancyCounts {
+            start,
+            approaches: self.approaches,
+            logins: self.logins,
+            conversions: self.conversions,
+            average_dwell_ms: if self.approaches == 0 {
+                0
+            } else {
+                self.dwell_ms / self.approaches as u64
+            },
+        }
+    }
+}
+
+/// Presence sessions and kiosk logins kept for occupancy statistics.
+/// Stored as one JSON file next to the config, like the print history.
+pub struct OccupancyLog {
+    data: Mutex<OccupancyData>,
+    config: Arc<ConfigStore>, // Retention is read on every prune, so changes apply live
+    path: PathBuf,
+    logger: Arc<Logger>,
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.with_timezone(&Local))
+}
+
+fn bucket_start(time: DateTime<Local>, bucket: StatsBucket) -> Option<DateTime<Local>> {
+    let hour = match bucket {
+        StatsBucket::Hour => time.hour(),
+        StatsBucket::Day => 0,
+    };
+    let start = time.date_naive().and_hms_opt(hour, 0, 0)?;
+    Local.from_local_datetime(&start).earliest()
+}
+
+impl OccupancyLog {
+    pub fn start(
+        config: &Arc<ConfigStore>,
+        logger: Arc<Logger>,
+    ) -> Result<Arc<Self>, Box<dyn std::error::Error>> {
+        let path = get_config_dir(Arc::clone(&logger))?.join("occupancy.json");
+
+        let data = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                logger
+                    .log(
+                        LogLevel::WARN,
+                        &format!("Discarding unreadable occupancy data: {}", e),
+                    )
+                    .ok();
+                OccupancyData::default()
+            }),
+            Err(_) => OccupancyData::default(),
+        };
+
+        let log = Arc::new(OccupancyLog {
+            data: Mutex::new(data),
+            config: Arc::clone(config),
+            path,
+            logger,
+        });
+        let mut data = log.lock_data();
+        log.prune(&mut data);
+        drop(data);
+
+        Ok(log)
+    }
+
+    /// Store a finished presence session from the human sensor thread
+    pub fn record_session(&self, left: &PersonLeft) {
+        let mut data = self.lock_data();
+        data.sessions.push(PresenceSession {
+            arrived_at: left.arrived_at.clone(),
+            left_at: left.left_at.clone(),
+            session_ms: left.session_ms,
+        });
+        self.prune(&mut data);
+        self.save(&data);
+    }
+
+    /// Store a user logging in at the kiosk
+    pub fn record_login(&self) {
+        let mut data = self.lock_data();
+        data.logins.push(Local::now().to_rfc3339());
+        self.prune(&mut data);
+        self.save(&data);
+    }
+
+    pub fn stats(&self, range: &StatsRange, bucket: StatsBucket) -> Result<OccupancyStats, String> {
+        let parse_date = |value: &str| {
+            NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+                .map_err(|_| format!("Invalid date: {}", value))
+        };
+        let from_date = parse_date(&range.from)?;
+        let to_date = parse_date(&range.to)?;
+        if to_date < from_date {
+            return Err("Range ends before it starts".to_string());
+        }
+        let to_local = |date: NaiveDate| {
+            date.and_hms_opt(0, 0, 0)
+                .and_then(|start| Local.from_local_datetime(&start).earliest())
+                .ok_or_else(|| format!("Invalid local date: {}", date))
+        };
+        let from = to_local(from_date)?;
+        let to = to_local(to_date + ChronoDuration::days(1))?;
+
+        let step = match bucket {
+            StatsBucket::Hour => ChronoDuration::hours(1),
+            StatsBucket::Day => ChronoDuration::days(1),
+        };
+        if bucket == StatsBucket::Hour && (to - from).num_hours() > MAX_HOURLY_BUCKETS {
+            return Err(format!(
+                "Hourly statistics cover at most {} days",
+                MAX_HOURLY_BUCKETS / 24
+            ));
+        }
+
+        // Every bucket in the range, so quiet periods show up as zero
+        let mut tallies: BTreeMap<DateTime<Local>, Tally> = BTreeMap::new();
+        let mut cursor = from;
+        while cursor < to {
+            if let Some(start) = bucket_start(cursor, bucket) {
+                tallies.entry(start).or_default();
+            }
+            cursor += step;
+        }
+
+        let data = self.lock_data();
+        let logins: Vec<DateTime<Local>> =
+            data.logins.iter().filter_map(|t| parse_time(t)).collect();
+
+        for login in logins.iter().filter(|t| **t >= from && **t < to) {
+            if let Some(tally) = bucket_start(*login, bucket).and_then(|s| tallies.get_mut(&s)) {
+                tally.logins += 1;
+            }
+        }
+
+        for session in &data.sessions {
+            let (Some(arrived), Some(left)) = (
+                parse_time(&session.arrived_at),
+                parse_time(&session.left_at),
+            ) else {
+                continue;
+            };
+            if arrived < from || arrived >= to {
+                continue;
+            }
+            let Some(tally) = bucket_start(arrived, bucket).and_then(|s| tallies.get_mut(&s))
+            else {
+                continue;
+            };
+            tally.approaches += 1;
+            tally.dwell_ms += session.session_ms;
+            if logins
+                .iter()
+                .any(|login| *login >= arrived && *login <= left)
+            {
+                tally.conversions += 1;
+            }
+        }
+
+        let mut total = Tally::default();
+        let buckets = tallies
+            .into_iter()
+            .map(|(start, tally)| {
+                total.approaches += tally.approaches;
+                total.logins += tally.logins;
+                total.conversions += tally.conversions;
+                total.dwell_ms += tally.dwell_ms;
+                tally.counts(start.to_rfc3339())
+            })
+            .collect();
+
+        Ok(OccupancyStats {
+            bucket,
+            buckets,
+            conversion_rate: if total.approaches == 0 {
+                0.0
+            } else {
+                total.conversions as f64 / total.approaches as f64
+            },
+            total: total.counts(from.to_rfc3339()),
+        })
+    }
+
+    /// Delete sessions and logins older than the retention period
+    fn prune(&self, data: &mut OccupancyData) {
+        let retention_days = self.config.with(|config| config.occupancy.retention_days);
+        let cutoff = Local::now() - ChronoDuration::days(retention_days as i64);
+        let keep = |time: &str| parse_time(time).is_some_and(|time| time > cutoff);
+        data.sessions.retain(|session| keep(&session.left_at));
+        data.logins.retain(|login| keep(login));
+    }
+
+    fn save(&self, data: &OccupancyData) {
+        let result = serde_json::to_string(data)
+            .map_err(|e| e.to_string())
+            .and_then(|content| fs::write(&self.path, content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            self.logger
+                .log_error(
+                    &format!("Failed to save occupancy data: {}", e),
+                    file!(),
+                    "save",
+                    line!(),
+                )
+                .ok();
+        }
+    }
+
+    fn lock_data(&self) -> std::sync::MutexGuard<'_, OccupancyData> {
+        match self.data.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Approaches, logins, dwell time and conversion rate per hour or day
+#[tauri::command]
+pub fn get_occupancy_stats(
+    range: StatsRange,
+    bucket: StatsBucket,
+    occupancy: State<'_, Arc<OccupancyLog>>,
+) -> Result<OccupancyStats, String> {
+    occupancy.stats(&range, bucket)
+}
@@ -0,0 +1,292 @@
+use crate::config_store::ConfigStore;
+use crate::logger::{LogLevel, Logger};
+use crate::printer::{resolve_printer_profile, PrintOutcome};
+use crate::store::{get_config_dir, Config, PrinterProfile, PrinterTransport};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, State};
+
+/// Paper counters for one printer since its roll was last replaced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperCounter {
+    pub lines_fed: u64,
+    pub cuts: u64,
+    pub used_mm: f64,
+    pub roll_length_mm: u32,
+    pub low_paper_threshold_mm: u32,
+    pub roll_replaced_at: String,
+    #[serde(default)]
+    pub near_end: Option<bool>, // Last roll sensor reading, if the printer reports one
+    #[serde(default)]
+    pub warned: bool, // A low-paper warning was already sent for this roll
+}
+
+impl PaperCounter {
+    pub fn remaining_mm(&self) -> f64 {
+        (self.roll_length_mm as f64 - self.used_mm).max(0.0)
+    }
+
+    pub fn is_low(&self) -> bool {
+        self.near_end == Some(true) || self.remaining_mm() <= self.low_paper_threshold_mm as f64
+    }
+}
+
+/// Payload of `get_paper_status` and the `low-paper-warning` event
+#[derive(Debug, Clone, Serialize)]
+pub struct PaperStatus {
+    printer: String,
+    lines_fed: u64,
+    cuts: u64,
+    used_mm: f64,
+    remaining_mm: f64,
+    roll_length_mm: u32,
+    near_end: Option<bool>,
+    low: bool,
+    roll_replaced_at: String,
+}
+
+impl PaperStatus {
+    fn new(printer: &str, counter: &PaperCounter) -> Self {
+        PaperStatus {
+            printer: printer.to_string(),
+            lines_fed: counter.lines_fed,
+            cuts: counter.cuts,
+            used_mm: counter.used_mm,
+            remaining_mm: counter.remaining_mm(),
+            roll_length_mm: counter.roll_length_mm,
+            near_end: counter.near_end,
+            low: counter.is_low(),
+            roll_replaced_at: counter.roll_replaced_at.clone(),
+        }
+    }
+}
+
+/// Tracks paper used per printer and predicts when a roll runs out.
+/// The estimate comes from the lines and cuts of every printed job; printers with
+/// a near-end sensor override it as soon as the sensor trips.
+pub struct PaperTracker {
+    counters: Mutex<HashMap<String, PaperCounter>>,
+    path: PathBuf,
+    logger: Arc<Logger>,
+    app_handle: AppHandle,
+}
+
+impl PaperTracker {
+    pub fn start(
+        app_handle: AppHandle,
+        logger: Arc<Logger>,
+    ) -> Result<Arc<Self>, Box<dyn std::error::Error>> {
+        let path = get_config_dir(Arc::clone(&logger))?.join("paper_usage.json");
+        let counters = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                logger
+                    .log(
+                        LogLevel::WARN,
+                        &format!("Discarding unreadable paper counters: {}", e),
+                    )
+                    .ok();
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+
+        Ok(Arc::new(PaperTracker {
+            counters: Mutex::new(counters),
+            path,
+            logger,
+            app_handle,
+        }))
+    }
+
+    /// Add the paper used by a completed job
+    pub fn record(&self, outcome: &PrintOutcome) {
+        let mut counters = self.lock_counters();
+        let counter = counters.entry(outcome.printer.clone()).or_insert_with(|| {
+            new_roll(
+                outcome.profile.roll_length_mm,
+                outcome.profile.low_paper_threshold_mm,
+            )
+        });
+
+        counter.lines_fed += outcome.usage.lines;
+        counter.cuts += outcome.usage.cuts;
+        counter.used_mm += outcome.usage.length_mm;
+        counter.roll_length_mm = outcome.profile.roll_length_mm;
+        counter.low_paper_threshold_mm = outcome.profile.low_paper_threshold_mm;
+        if outcome.paper_near_end.is_some() {
+            counter.near_end = outcome.paper_near_end;
+        }
+
+        if counter.is_low() && !counter.warned {
+            counter.warned = true;
+            let status = PaperStatus::new(&outcome.printer, counter);
+            self.logger
+                .log(
+                    LogLevel::WARN,
+                    &format!(
+                        "Paper low on printer {}: about {:.0} mm left (near-end sensor: {:?})",
+                        outcome.printer, status.remaining_mm, status.near_end
+                    ),
+                )
+                .ok();
+            if let Err(e) = self.app_handle.emit("low-paper-warning", status) {
+                println!("Failed to emit low-paper-warning event: {}", e);
+            }
+        }
+
+        self.save(&counters);
+    }
+
+    /// Start counting a fresh roll, sized by the printer's current profile; returns the new status
+    pub fn reset(&self, config: &Config, printer: &str) -> Result<PaperStatus, String> {
+        let counter = fresh_roll(config, printer)?;
+        let status = PaperStatus::new(printer, &counter);
+        let mut counters = self.lock_counters();
+        counters.insert(printer.to_string(), counter);
+        self.save(&counters);
+
+        self.logger
+            .log(
+                LogLevel::INFO,
+                &format!("Paper roll replaced on printer {}", printer),
+            )
+            .ok();
+        Ok(status)
+    }
+
+    pub fn status(&self) -> Vec<PaperStatus> {
+        self.lock_counters()
+            .iter()
+            .map(|(printer, counter)| PaperStatus::new(printer, counter))
+            .collect()
+    }
+
+    fn save(&self, counters: &HashMap<String, PaperCounter>) {
+        let result = serde_json::to_string_pretty(counters)
+            .map_err(|e| e.to_string())
+            .and_then(|content| fs::write(&self.path, content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            self.logger
+                .log_error(
+                    &format!("Failed to save paper counters: {}", e),
+                    file!(),
+                    "save",
+                    line!(),
+                )
+                .ok();
+        }
+    }
+
+    fn lock_counters(&self) -> std::sync::MutexGuard<'_, HashMap<String, PaperCounter>> {
+        match self.counters.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+fn new_roll(roll_length_mm: u32, low_paper_threshold_mm: u32) -> PaperCounter {
+    PaperCounter {
+        lines_fed: 0,
+        cuts: 0,
+        used_mm: 0.0,
+        roll_length_mm,
+        low_paper_threshold_mm,
+        roll_replaced_at: Local::now().to_rfc3339(),
+        near_end: None,
+        warned: false,
+    }
+}
+
+/// Profile of a printer as counters name it: by port, or by profile for printers
+/// not on a serial port
+fn printer_profile(config: &Config, printer: &str) -> Result<PrinterProfile, String> {
+    match config
+        .printer_profiles
+        .iter()
+        .find(|profile| profile.name == printer && profile.transport != PrinterTransport::Serial)
+    {
+        Some(profile) => Ok(profile.clone()),
+        None => resolve_printer_profile(config, printer, None),
+    }
+}
+
+fn fresh_roll(config: &Config, printer: &str) -> Result<PaperCounter, String> {
+    let profile = printer_profile(config, printer)?;
+    Ok(new_roll(
+        profile.roll_length_mm,
+        profile.low_paper_threshold_mm,
+    ))
+}
+
+#[tauri::command]
+pub fn get_paper_status(paper: State<'_, Arc<PaperTracker>>) -> Result<Vec<PaperStatus>, String> {
+    Ok(paper.status())
+}
+
+#[tauri::command]
+pub fn reset_paper_roll(
+    printer: String,
+    paper: State<'_, Arc<PaperTracker>>,
+    config: State<'_, Arc<ConfigStore>>,
+) -> Result<PaperStatus, String> {
+    paper.reset(&config.get(), &printer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::default_config;
+
+    fn config() -> Config {
+        let mut config = default_config();
+        let printer = config
+            .serialdata
+            .iter_mut()
+            .find(|entry| entry.name == "PRINTER")
+            .unwrap();
+        printer.port = "COM5".to_string();
+        printer.profile = Some("58mm".to_string());
+        let profile = config
+            .printer_profiles
+            .iter_mut()
+            .find(|profile| profile.name == "58mm")
+            .unwrap();
+        profile.roll_length_mm = 50_000;
+        profile.low_paper_threshold_mm = 2_000;
+        config
+    }
+
+    #[test]
+    fn fresh_roll_for_a_printer_without_a_counter_uses_its_profile() {
+        let counter = fresh_roll(&config(), "COM5").unwrap();
+
+        assert_eq!(counter.roll_length_mm, 50_000);
+        assert_eq!(counter.low_paper_threshold_mm, 2_000);
+        assert_eq!(counter.used_mm, 0.0);
+        assert!(!counter.warned);
+    }
+
+    #[test]
+    fn fresh_roll_for_a_network_printer_uses_the_profile_it_is_named_after() {
+        let mut config = config();
+        let profile = config
+            .printer_profiles
+            .iter_mut()
+            .find(|profile| profile.name == "80mm")
+            .unwrap();
+        profile.transport = PrinterTransport::Tcp {
+            host: "192.168.1.50".to_string(),
+            port: 9100,
+        };
+        profile.roll_length_mm = 80_000;
+
+        let counter = fresh_roll(&config, "80mm").unwrap();
+        assert_eq!(counter.roll_length_mm, 80_000);
+        assert_eq!(counter.low_paper_threshold_mm, 5_000);
+    }
+}
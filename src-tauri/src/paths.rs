@@ -0,0 +1,122 @@
+use crate::logger::LogLevel;
+use once_cell::sync::Lazy;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const HOME_ENV: &str = "SNU_KIOSK_HOME";
+const HOME_ARG: &str = "--kiosk-home";
+pub const APP_DIR_NAME: &str = "wise-neosco-kiosk-snu"; // Config folder of existing Windows installs
+const LEGACY_LOG_DIR_NAME: &str = "wise-kiosk-app"; // Logs were kept apart before the folders were merged
+
+static APP_HOME: Lazy<Result<PathBuf, String>> = Lazy::new(resolve_app_home);
+// Messages from resolving the folders, kept until the logger exists
+static STARTUP_MESSAGES: Mutex<Vec<(LogLevel, String)>> = Mutex::new(Vec::new());
+
+fn report(level: LogLevel, message: String) {
+    match STARTUP_MESSAGES.lock() {
+        Ok(mut messages) => messages.push((level, message)),
+        Err(poisoned) => poisoned.into_inner().push((level, message)),
+    }
+}
+
+fn non_empty_var(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+// `--kiosk-home <dir>` or `--kiosk-home=<dir>`
+fn home_from_args() -> Option<PathBuf> {
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == HOME_ARG {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg
+            .strip_prefix(HOME_ARG)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(PathBuf::from(value));
+        }
+    }
+    None
+}
+
+// Per-user data directory of the platform
+fn platform_data_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        non_empty_var("APPDATA").or_else(|| {
+            non_empty_var("USERPROFILE").map(|profile| profile.join("AppData").join("Roaming"))
+        })
+    } else if cfg!(target_os = "macos") {
+        non_empty_var("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        non_empty_var("XDG_DATA_HOME")
+            .or_else(|| non_empty_var("HOME").map(|home| home.join(".local").join("share")))
+    }
+}
+
+// Move logs from the old separate folder next to the config. If the move
+// fails (e.g. the folder is in use) the old logs stay where they are.
+fn migrate_legacy_logs(data_dir: &Path, home: &Path) {
+    let legacy_dir = data_dir.join(LEGACY_LOG_DIR_NAME);
+    let legacy_logs = legacy_dir.join("logs");
+    let logs = home.join("logs");
+    if !legacy_logs.is_dir() || logs.exists() {
+        return;
+    }
+
+    match fs::create_dir_all(home).and_then(|_| fs::rename(&legacy_logs, &logs)) {
+        Ok(()) => {
+            report(
+                LogLevel::INFO,
+                format!(
+                    "Moved logs from {} to {}",
+                    legacy_logs.display(),
+                    logs.display()
+                ),
+            );
+            fs::remove_dir(&legacy_dir).ok(); // Only succeeds once it is empty
+        }
+        Err(e) => report(
+            LogLevel::WARN,
+            format!("Failed to move logs from {}: {}", legacy_logs.display(), e),
+        ),
+    }
+}
+
+fn resolve_app_home() -> Result<PathBuf, String> {
+    if let Some(home) = home_from_args().or_else(|| non_empty_var(HOME_ENV)) {
+        return Ok(home);
+    }
+
+    let data_dir = platform_data_dir().ok_or_else(|| {
+        format!(
+            "Cannot find the app data directory; set {} or pass {} to choose one",
+            HOME_ENV, HOME_ARG
+        )
+    })?;
+    let home = data_dir.join(APP_DIR_NAME);
+    migrate_legacy_logs(&data_dir, &home);
+    Ok(home)
+}
+
+/// Folder holding the config file, logs and other persisted kiosk state.
+/// `--kiosk-home` wins over `SNU_KIOSK_HOME`, which wins over the platform data directory.
+pub fn app_home() -> Result<PathBuf, String> {
+    APP_HOME.clone()
+}
+
+pub fn log_dir() -> Result<PathBuf, String> {
+    Ok(app_home()?.join("logs"))
+}
+
+/// Messages about moving old folders, to be logged once a logger is available
+pub fn take_startup_messages() -> Vec<(LogLevel, String)> {
+    match STARTUP_MESSAGES.lock() {
+        Ok(mut messages) => std::mem::take(&mut *messages),
+        Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+    }
+}
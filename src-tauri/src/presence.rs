@@ -0,0 +1,388 @@
+use crate::store::{HumanSensorSettings, PresenceDebounce, ScheduleDay, SensorScheduleWindow};
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, NaiveTime, Weekday};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Payload of the `person-arrived` event
+#[derive(Debug, Clone, Serialize)]
+pub struct PersonArrived {
+    pub arrived_at: String,
+}
+
+/// Payload of the `person-left` event
+#[derive(Debug, Clone, Serialize)]
+pub struct PersonLeft {
+    pub arrived_at: String,
+    pub left_at: String,
+    pub session_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum PresenceEvent {
+    Arrived(PersonArrived),
+    Left(PersonLeft),
+}
+
+/// Turns flickering raw sensor samples into presence sessions.
+///
+/// Samples are smoothed into an activity level. While nobody is present the level must stay
+/// at or above `on_level` for `min_on_ms` before a person counts as arrived; while present it
+/// must stay at or below `off_level` for `min_off_ms` before they count as gone. The gap
+/// between the two levels is the hysteresis that keeps short flickers from ending a session.
+pub struct PresenceDetector {
+    settings: PresenceDebounce,
+    level: f64, // Smoothed activity, 0-100
+    last_sample: Option<Instant>,
+    present: bool,
+    // When the level first crossed the threshold towards the other state
+    pending_since: Option<(Instant, DateTime<Local>)>,
+    arrived_at: Option<(Instant, DateTime<Local>)>,
+}
+
+impl PresenceDetector {
+    pub fn new(settings: PresenceDebounce) -> Self {
+        PresenceDetector {
+            settings,
+            level: 0.0,
+            last_sample: None,
+            present: false,
+            pending_since: None,
+            arrived_at: None,
+        }
+    }
+
+    /// Feed one raw sample; returns an event when the debounced state changes
+    pub fn update(&mut self, detected: bool, now: Instant) -> Option<PresenceEvent> {
+        let target = if detected { 100.0 } else { 0.0 };
+        let elapsed = self
+            .last_sample
+            .map(|last| now.saturating_duration_since(last))
+            .unwrap_or_default();
+        self.last_sample = Some(now);
+        if self.settings.smoothing_ms == 0 {
+            self.level = target;
+        } else {
+            let alpha =
+                1.0 - (-(elapsed.as_millis() as f64) / self.settings.smoothing_ms as f64).exp();
+            self.level += (target - self.level) * alpha;
+        }
+
+        let crossing = if self.present {
+            self.level <= self.settings.off_level as f64
+        } else {
+            self.level >= self.settings.on_level as f64
+        };
+        if !crossing {
+            self.pending_since = None;
+            return None;
+        }
+
+        let (since, since_local) = *self.pending_since.get_or_insert((now, Local::now()));
+        let hold = Duration::from_millis(if self.present {
+            self.settings.min_off_ms
+        } else {
+            self.settings.min_on_ms
+        });
+        if now.saturating_duration_since(since) < hold {
+            return None;
+        }
+        self.pending_since = None;
+
+        if self.present {
+            // The session ended when the activity first dropped, not when the hold ran out
+            self.present = false;
+            let (arrived, arrived_local) = self.arrived_at.take()?;
+            Some(PresenceEvent::Left(PersonLeft {
+                arrived_at: arrived_local.to_rfc3339(),
+                left_at: since_local.to_rfc3339(),
+                session_ms: since.saturating_duration_since(arrived).as_millis() as u64,
+            }))
+        } else {
+            self.present = true;
+            self.arrived_at = Some((since, since_local));
+            Some(PresenceEvent::Arrived(PersonArrived {
+                arrived_at: since_local.to_rfc3339(),
+            }))
+        }
+    }
+
+    /// Switch debounce settings, e.g. when a schedule window starts; the current state is kept
+    pub fn set_debounce(&mut self, settings: PresenceDebounce) {
+        self.settings = settings;
+    }
+
+    /// Close an open session, e.g. when monitoring stops
+    pub fn end_session(&mut self) -> Option<PresenceEvent> {
+        self.present = false;
+        self.pending_since = None;
+        let (arrived, arrived_local) = self.arrived_at.take()?;
+        Some(PresenceEvent::Left(PersonLeft {
+            arrived_at: arrived_local.to_rfc3339(),
+            left_at: Local::now().to_rfc3339(),
+            session_ms: arrived.elapsed().as_millis() as u64,
+        }))
+    }
+}
+
+/// How the human sensor is used at a given time
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SensorMode {
+    pub active: bool,
+    pub window: Option<String>, // Name of the schedule window in effect
+    pub debounce: PresenceDebounce,
+}
+
+pub fn parse_schedule_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+fn schedule_day(day: Weekday) -> ScheduleDay {
+    match day {
+        Weekday::Mon => ScheduleDay::Mon,
+        Weekday::Tue => ScheduleDay::Tue,
+        Weekday::Wed => ScheduleDay::Wed,
+        Weekday::Thu => ScheduleDay::Thu,
+        Weekday::Fri => ScheduleDay::Fri,
+        Weekday::Sat => ScheduleDay::Sat,
+        Weekday::Sun => ScheduleDay::Sun,
+    }
+}
+
+fn window_contains(window: &SensorScheduleWindow, now: NaiveDateTime) -> bool {
+    let (Some(start), Some(end)) = (
+        parse_schedule_time(&window.start),
+        parse_schedule_time(&window.end),
+    ) else {
+        return false;
+    };
+    let time = now.time();
+    // The day the window started on
+    let day = if start == end
+        || (start < end && start <= time && time < end)
+        || (start > end && time >= start)
+    {
+        now.weekday()
+    } else if start > end && time < end {
+        now.weekday().pred()
+    } else {
+        return false;
+    };
+    window.days.is_empty() || window.days.contains(&schedule_day(day))
+}
+
+/// Evaluate the HUMAN_SENSOR schedule on top of the `humanSensorDetection` flag
+pub fn scheduled_mode(
+    settings: &HumanSensorSettings,
+    detection_enabled: bool,
+    now: NaiveDateTime,
+) -> SensorMode {
+    let window = settings
+        .schedule
+        .iter()
+        .find(|window| window_contains(window, now));
+    SensorMode {
+        active: detection_enabled && !window.is_some_and(|window| window.disabled),
+        window: window.map(|window| {
+            if window.name.is_empty() {
+                format!("{}-{}", window.start, window.end)
+            } else {
+                window.name.clone()
+            }
+        }),
+        debounce: window
+            .and_then(|window| window.debounce)
+            .unwrap_or(settings.debounce),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn debounce(min_on_ms: u64, min_off_ms: u64, smoothing_ms: u64) -> PresenceDebounce {
+        PresenceDebounce {
+            min_on_ms,
+            min_off_ms,
+            on_level: 50,
+            off_level: 20,
+            smoothing_ms,
+        }
+    }
+
+    // Feed one sample every 100 ms from `from_ms` up to `to_ms` (exclusive) and return
+    // the time of each event with the session length of every departure
+    fn feed(
+        detector: &mut PresenceDetector,
+        start: Instant,
+        from_ms: u64,
+        to_ms: u64,
+        detected: bool,
+    ) -> Vec<(u64, Option<u64>)> {
+        (from_ms..to_ms)
+            .step_by(100)
+            .filter_map(|ms| {
+                let event = detector.update(detected, start + Duration::from_millis(ms))?;
+                Some(match event {
+                    PresenceEvent::Arrived(_) => (ms, None),
+                    PresenceEvent::Left(left) => (ms, Some(left.session_ms)),
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn arrival_waits_for_min_on_time() {
+        let mut detector = PresenceDetector::new(debounce(500, 1000, 0));
+        let start = Instant::now();
+        assert_eq!(feed(&mut detector, start, 0, 1000, true), vec![(500, None)]);
+    }
+
+    #[test]
+    fn short_detections_are_not_arrivals() {
+        let mut detector = PresenceDetector::new(debounce(500, 1000, 0));
+        let start = Instant::now();
+        assert_eq!(feed(&mut detector, start, 0, 400, true), vec![]);
+        assert_eq!(feed(&mut detector, start, 400, 500, false), vec![]);
+        // The hold starts over after the gap
+        assert_eq!(
+            feed(&mut detector, start, 500, 1200, true),
+            vec![(1000, None)]
+        );
+    }
+
+    #[test]
+    fn gaps_shorter_than_min_off_time_keep_the_session() {
+        let mut detector = PresenceDetector::new(debounce(0, 1000, 0));
+        let start = Instant::now();
+        assert_eq!(feed(&mut detector, start, 0, 2000, true), vec![(0, None)]);
+        assert_eq!(feed(&mut detector, start, 2000, 2900, false), vec![]);
+        assert_eq!(feed(&mut detector, start, 2900, 4000, true), vec![]);
+        // The session ends when the activity first dropped, not when the hold ran out
+        assert_eq!(
+            feed(&mut detector, start, 4000, 6000, false),
+            vec![(5000, Some(4000))]
+        );
+    }
+
+    #[test]
+    fn smoothing_delays_crossing_the_levels() {
+        let mut detector = PresenceDetector::new(debounce(0, 0, 1000));
+        let start = Instant::now();
+        // 100 * (1 - e^(-t/1000)) first reaches the on level of 50 at 700 ms
+        assert_eq!(
+            feed(&mut detector, start, 0, 10_000, true),
+            vec![(700, None)]
+        );
+        // and decays to the off level of 20 within 1700 ms of the last detection
+        assert_eq!(
+            feed(&mut detector, start, 10_000, 15_000, false),
+            vec![(11_600, Some(10_900))]
+        );
+    }
+
+    #[test]
+    fn brief_dropouts_stay_above_the_off_level() {
+        let mut detector = PresenceDetector::new(debounce(0, 0, 1000));
+        let start = Instant::now();
+        assert_eq!(feed(&mut detector, start, 0, 5000, true), vec![(700, None)]);
+        // Half a second without detection only brings the level down to about 60
+        assert_eq!(feed(&mut detector, start, 5000, 5600, false), vec![]);
+        assert_eq!(feed(&mut detector, start, 5600, 8000, true), vec![]);
+    }
+
+    #[test]
+    fn end_session_closes_an_open_session_once() {
+        let mut detector = PresenceDetector::new(debounce(0, 1000, 0));
+        assert!(detector.end_session().is_none());
+        feed(&mut detector, Instant::now(), 0, 100, true);
+        assert!(matches!(
+            detector.end_session(),
+            Some(PresenceEvent::Left(_))
+        ));
+        assert!(detector.end_session().is_none());
+    }
+
+    fn window(start: &str, end: &str, days: &[ScheduleDay]) -> SensorScheduleWindow {
+        SensorScheduleWindow {
+            name: String::new(),
+            start: start.to_string(),
+            end: end.to_string(),
+            days: days.to_vec(),
+            disabled: false,
+            debounce: None,
+        }
+    }
+
+    // 2026-10-19 is a Monday
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn same_day_window_includes_start_and_excludes_end() {
+        let lunch = window("12:00", "13:00", &[]);
+        assert!(!window_contains(&lunch, at("2026-10-19 11:59")));
+        assert!(window_contains(&lunch, at("2026-10-19 12:00")));
+        assert!(window_contains(&lunch, at("2026-10-19 12:59")));
+        assert!(!window_contains(&lunch, at("2026-10-19 13:00")));
+    }
+
+    #[test]
+    fn window_crossing_midnight_belongs_to_the_day_it_starts() {
+        let night = window("22:00", "06:00", &[ScheduleDay::Mon]);
+        assert!(!window_contains(&night, at("2026-10-19 21:59")));
+        assert!(window_contains(&night, at("2026-10-19 22:00")));
+        assert!(window_contains(&night, at("2026-10-19 23:59")));
+        // Tuesday morning is still Monday night
+        assert!(window_contains(&night, at("2026-10-20 00:00")));
+        assert!(window_contains(&night, at("2026-10-20 05:59")));
+        assert!(!window_contains(&night, at("2026-10-20 06:00")));
+        // Tuesday night and Monday morning (Sunday night) are not listed
+        assert!(!window_contains(&night, at("2026-10-20 23:00")));
+        assert!(!window_contains(&night, at("2026-10-19 05:00")));
+    }
+
+    #[test]
+    fn window_with_equal_start_and_end_covers_the_whole_day() {
+        let monday = window("00:00", "00:00", &[ScheduleDay::Mon]);
+        assert!(window_contains(&monday, at("2026-10-19 00:00")));
+        assert!(window_contains(&monday, at("2026-10-19 23:59")));
+        assert!(!window_contains(&monday, at("2026-10-20 00:00")));
+    }
+
+    #[test]
+    fn window_with_invalid_times_never_matches() {
+        let broken = window("25:00", "06:00", &[]);
+        assert!(!window_contains(&broken, at("2026-10-19 01:00")));
+        assert!(!window_contains(&broken, at("2026-10-19 23:00")));
+    }
+
+    #[test]
+    fn first_matching_window_sets_the_mode() {
+        let quiet = debounce(2000, 1000, 0);
+        let mut night = window("22:00", "06:00", &[]);
+        night.name = "night".to_string();
+        night.disabled = true;
+        let mut lunch = window("12:00", "13:00", &[]);
+        lunch.debounce = Some(quiet);
+        let settings = HumanSensorSettings {
+            schedule: vec![night, lunch, window("00:00", "00:00", &[])],
+            ..HumanSensorSettings::default()
+        };
+
+        let mode = scheduled_mode(&settings, true, at("2026-10-19 23:00"));
+        assert!(!mode.active);
+        assert_eq!(mode.window.as_deref(), Some("night"));
+
+        let mode = scheduled_mode(&settings, true, at("2026-10-19 12:30"));
+        assert!(mode.active);
+        assert_eq!(mode.window.as_deref(), Some("12:00-13:00"));
+        assert_eq!(mode.debounce, quiet);
+
+        let mode = scheduled_mode(&settings, true, at("2026-10-19 09:00"));
+        assert_eq!(mode.window.as_deref(), Some("00:00-00:00"));
+        assert_eq!(mode.debounce, settings.debounce);
+
+        assert!(!scheduled_mode(&settings, false, at("2026-10-19 12:30")).active);
+    }
+}
@@ -0,0 +1,796 @@
+use crate::config_store::ConfigStore;
+use crate::layout::{char_width, pad_right, text_width};
+use crate::logger::{LogLevel, Logger};
+use crate::printer::{encode_print_commands, resolve_printer_profile, PrintCommand};
+use crate::store::{CutStyle, PrinterProfile};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use encoding_rs::Encoding;
+use serde::Serialize;
+use std::sync::Arc;
+
+// Font cell sizes in printer dots
+const FONT_A: (usize, usize) = (12, 24);
+const FONT_B: (usize, usize) = (9, 17);
+const DEFAULT_LINE_SPACING: usize = 30; // ESC 2 - 30 dots for a 24 dot font
+const QR_MODULE_DOTS: usize = 5; // Module size used by the qr_code command
+const QR_GAP: usize = 6;
+const DEFAULT_BARCODE_HEIGHT: usize = 162; // GS h default
+const BARCODE_MODULE_DOTS: usize = 2; // Module width used by the barcode command
+const CUT_HEIGHT: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TextStyle {
+    bold: bool,
+    underline: usize, // Underline thickness in dots, 0 for none
+    reverse: bool,
+    font_b: bool,
+    width: usize,        // Character width multiplier
+    height: usize,       // Character height multiplier
+    char_spacing: usize, // Extra dots after each character
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        TextStyle {
+            bold: false,
+            underline: 0,
+            reverse: false,
+            font_b: false,
+            width: 1,
+            height: 1,
+            char_spacing: 0,
+        }
+    }
+}
+
+impl TextStyle {
+    /// Horizontal space a character takes on the paper
+    fn advance(&self, c: char) -> usize {
+        let font_width = if self.font_b { FONT_B.0 } else { FONT_A.0 };
+        char_width(c) * (font_width + self.char_spacing) * self.width
+    }
+
+    fn cell_height(&self) -> usize {
+        let font_height = if self.font_b { FONT_B.1 } else { FONT_A.1 };
+        font_height * self.height
+    }
+
+    /// Styles other than plain text, as listed next to a line in the text preview
+    fn markers(&self) -> Vec<String> {
+        let mut markers = Vec::new();
+        if self.bold {
+            markers.push("bold".to_string());
+        }
+        match self.underline {
+            0 => {}
+            1 => markers.push("underline".to_string()),
+            n => markers.push(format!("underline{}", n)),
+        }
+        if self.reverse {
+            markers.push("reverse".to_string());
+        }
+        if self.font_b {
+            markers.push("fontB".to_string());
+        }
+        if self.width != 1 || self.height != 1 {
+            markers.push(format!("{}x{}", self.width, self.height));
+        }
+        if self.char_spacing != 0 {
+            markers.push(format!("spacing{}", self.char_spacing));
+        }
+        markers
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+    text: String,
+    style: TextStyle,
+}
+
+#[derive(Debug, Clone)]
+enum PreviewLine {
+    Text {
+        align: Align,
+        segments: Vec<Segment>,
+        line_spacing: usize,
+    },
+    Qr {
+        align: Align,
+        data: String,
+    },
+    Barcode {
+        align: Align,
+        data: String,
+        height: usize,
+    },
+    Cut(CutStyle),
+}
+
+/// Result of `render_print_preview`
+#[derive(Debug, Serialize)]
+pub struct PrintPreview {
+    pub text: String,       // Monospace rendering, one framed line per printed line
+    pub png_base64: String, // Dot-accurate layout image with glyphs drawn as boxes
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Replays an ESC/POS byte stream the way the printer would lay it out
+struct Interpreter<'a> {
+    profile: &'a PrinterProfile,
+    encoding: &'static Encoding,
+    line_dots: usize, // Printable width
+    lines: Vec<PreviewLine>,
+    align: Align,
+    style: TextStyle,
+    line_spacing: usize,
+    barcode_height: usize,
+    code_page: u8,
+    segments: Vec<Segment>,
+    pending: Vec<u8>, // Undecoded text bytes in the current style and code page
+    x: usize,         // Dots used on the current line
+}
+
+impl<'a> Interpreter<'a> {
+    fn new(profile: &'a PrinterProfile) -> Result<Self, String> {
+        let encoding = Encoding::for_label(profile.encoding.as_bytes())
+            .ok_or_else(|| format!("Unsupported printer encoding: {}", profile.encoding))?;
+        Ok(Interpreter {
+            profile,
+            encoding,
+            line_dots: profile.chars_per_line as usize * FONT_A.0,
+            lines: Vec::new(),
+            align: Align::Left,
+            style: TextStyle::default(),
+            line_spacing: DEFAULT_LINE_SPACING,
+            barcode_height: DEFAULT_BARCODE_HEIGHT,
+            code_page: profile.default_code_page,
+            segments: Vec::new(),
+            pending: Vec::new(),
+            x: 0,
+        })
+    }
+
+    fn run(mut self, bytes: &[u8]) -> Vec<PreviewLine> {
+        let mut i = 0;
+        while i < bytes.len() {
+            let arg = |offset: usize| bytes.get(i + offset).copied().unwrap_or(0);
+            match bytes[i] {
+                b'\n' => {
+                    self.end_line();
+                    i += 1;
+                }
+                b'\r' => i += 1,
+                0x1B => {
+                    match arg(1) {
+                        b'@' => {
+                            self.flush_text();
+                            self.style = TextStyle::default();
+                            self.align = Align::Left;
+                            self.line_spacing = DEFAULT_LINE_SPACING;
+                            self.code_page = self.profile.default_code_page;
+                            i += 2;
+                            continue;
+                        }
+                        b'2' => {
+                            self.line_spacing = DEFAULT_LINE_SPACING;
+                            i += 2;
+                            continue;
+                        }
+                        b'3' => self.line_spacing = arg(2) as usize,
+                        b'E' => self.set_style(|style| style.bold = arg(2) & 1 == 1),
+                        b'-' => {
+                            // 0-2 or '0'-'2'; the printer ignores any other value
+                            let thickness = match arg(2) {
+                                0 | b'0' => Some(0),
+                                1 | b'1' => Some(1),
+                                2 | b'2' => Some(2),
+                                _ => None,
+                            };
+                            if let Some(thickness) = thickness {
+                                self.set_style(|style| style.underline = thickness);
+                            }
+                        }
+                        b'M' => self.set_style(|style| style.font_b = arg(2) & 1 == 1),
+                        b' ' => self.set_style(|style| style.char_spacing = arg(2) as usize),
+                        b'a' => {
+                            self.align = match arg(2) {
+                                1 | b'1' => Align::Center,
+                                2 | b'2' => Align::Right,
+                                _ => Align::Left,
+                            }
+                        }
+                        b't' => {
+                            self.flush_text();
+                            self.code_page = arg(2);
+                        }
+                        b'd' => {
+                            self.end_line();
+                            for _ in 1..arg(2) {
+                                self.end_line();
+                            }
+                        }
+                        _ => {}
+                    }
+                    i += 3;
+                }
+                0x1D => match arg(1) {
+                    b'!' => {
+                        let n = arg(2) as usize;
+                        self.set_style(|style| {
+                            style.width = (n >> 4) + 1;
+                            style.height = (n & 0x0F) + 1;
+                        });
+                        i += 3;
+                    }
+                    b'B' => {
+                        self.set_style(|style| style.reverse = arg(2) & 1 == 1);
+                        i += 3;
+                    }
+                    b'V' => {
+                        self.flush_line();
+                        let style = match arg(2) {
+                            1 | b'1' | 66 => CutStyle::Partial,
+                            _ => CutStyle::Full,
+                        };
+                        self.lines.push(PreviewLine::Cut(style));
+                        // Function B (65/66) carries an extra feed argument
+                        i += if arg(2) >= 65 { 4 } else { 3 };
+                    }
+                    b'W' => {
+                        let dots = arg(2) as usize + arg(3) as usize * 256;
+                        self.line_dots = dots.min(self.profile.chars_per_line as usize * FONT_A.0);
+                        i += 4;
+                    }
+                    b'L' => i += 4,
+                    b'h' => {
+                        self.barcode_height = arg(2) as usize;
+                        i += 3;
+                    }
+                    b'k' => {
+                        // Function B barcodes carry their length; function A ones end with NUL
+                        let start = i + if arg(2) >= 65 { 4 } else { 3 };
+                        let end = if arg(2) >= 65 {
+                            (start + arg(3) as usize).min(bytes.len())
+                        } else {
+                            bytes[start.min(bytes.len())..]
+                                .iter()
+                                .position(|b| *b == 0)
+                                .map_or(bytes.len(), |pos| start + pos)
+                        };
+                        let data = String::from_utf8_lossy(&bytes[start.min(end)..end]);
+                        self.flush_line();
+                        self.lines.push(PreviewLine::Barcode {
+                            align: self.align,
+                            // Drop the CODE128 code set selector
+                            data: data
+                                .trim_start_matches("{B")
+                                .trim_start_matches("{A")
+                                .to_string(),
+                            height: self.barcode_height,
+                        });
+                        i = if arg(2) >= 65 { end } else { end + 1 };
+                        if bytes.get(i) == Some(&b'\n') {
+                            i += 1;
+                        }
+                    }
+                    _ => i += 3,
+                },
+                0x10 if arg(1) == 0x04 => i += 3, // DLE EOT n - status request, prints nothing
+                0x1A if arg(1) == b'B' => {
+                    // QR code: 1A 'B' 2 <len> <size> <data> 00 '\n'
+                    let len = arg(3) as usize;
+                    let start = (i + 5).min(bytes.len());
+                    let end = (start + len).min(bytes.len());
+                    self.flush_line();
+                    self.lines.push(PreviewLine::Qr {
+                        align: self.align,
+                        data: String::from_utf8_lossy(&bytes[start..end]).to_string(),
+                    });
+                    i = end;
+                    if bytes.get(i) == Some(&0x00) {
+                        i += 1;
+                    }
+                    if bytes.get(i) == Some(&b'\n') {
+                        i += 1;
+                    }
+                }
+                byte => {
+                    self.pending.push(byte);
+                    i += 1;
+                }
+            }
+        }
+        self.flush_line();
+        self.lines
+    }
+
+    fn set_style(&mut self, change: impl FnOnce(&mut TextStyle)) {
+        self.flush_text();
+        change(&mut self.style);
+    }
+
+    /// Decode pending bytes and add them to the line, wrapping like the printer does
+    fn flush_text(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let bytes = std::mem::take(&mut self.pending);
+        let text = if self.code_page == self.profile.code_page {
+            self.encoding.decode(&bytes).0.to_string()
+        } else {
+            String::from_utf8_lossy(&bytes).to_string()
+        };
+
+        let mut current = String::new();
+        for c in text.chars() {
+            let advance = self.style.advance(c);
+            if self.x + advance > self.line_dots && self.x > 0 {
+                self.push_segment(std::mem::take(&mut current));
+                self.end_line_inner();
+            }
+            current.push(c);
+            self.x += advance;
+        }
+        self.push_segment(current);
+    }
+
+    fn push_segment(&mut self, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        match self.segments.last_mut() {
+            Some(last) if last.style == self.style => last.text.push_str(&text),
+            _ => self.segments.push(Segment {
+                text,
+                style: self.style,
+            }),
+        }
+    }
+
+    fn end_line(&mut self) {
+        self.flush_text();
+        self.end_line_inner();
+    }
+
+    fn end_line_inner(&mut self) {
+        self.lines.push(PreviewLine::Text {
+            align: self.align,
+            segments: std::mem::take(&mut self.segments),
+            line_spacing: self.line_spacing,
+        });
+        self.x = 0;
+    }
+
+    /// Finish a partly filled line without adding an empty one
+    fn flush_line(&mut self) {
+        self.flush_text();
+        if !self.segments.is_empty() {
+            self.end_line_inner();
+        }
+    }
+}
+
+/// Columns used by the segments in the text preview
+fn segments_columns(segments: &[Segment]) -> usize {
+    segments
+        .iter()
+        .map(|segment| text_width(&segment.text) * segment.style.width)
+        .sum()
+}
+
+/// Dots used by the segments on the paper
+fn segments_dots(segments: &[Segment]) -> usize {
+    segments
+        .iter()
+        .flat_map(|segment| segment.text.chars().map(move |c| segment.style.advance(c)))
+        .sum()
+}
+
+fn align_offset(align: Align, used: usize, width: usize) -> usize {
+    match align {
+        Align::Left => 0,
+        Align::Center => width.saturating_sub(used) / 2,
+        Align::Right => width.saturating_sub(used),
+    }
+}
+
+/// Render the lines as framed monospace text.
+/// Widened characters are followed by spaces so columns line up with the paper,
+/// and styles other than plain text are listed after the frame. Font B fits more
+/// characters than there are columns, so the frame grows to the widest line.
+fn render_text(lines: &[PreviewLine], width: usize) -> String {
+    enum Row {
+        Text(String, Vec<String>),
+        Cut(&'static str),
+    }
+
+    let rows: Vec<Row> = lines
+        .iter()
+        .map(|line| match line {
+            PreviewLine::Text {
+                align,
+                segments,
+                line_spacing,
+            } => {
+                let used = segments_columns(segments);
+                let mut content = " ".repeat(align_offset(*align, used, width));
+                let mut markers: Vec<String> = Vec::new();
+                for segment in segments {
+                    for c in segment.text.chars() {
+                        content.push(c);
+                        content.push_str(&" ".repeat(char_width(c) * (segment.style.width - 1)));
+                    }
+                    for marker in segment.style.markers() {
+                        if !markers.contains(&marker) {
+                            markers.push(marker);
+                        }
+                    }
+                }
+                if *line_spacing != DEFAULT_LINE_SPACING {
+                    markers.push(format!("lf{}", line_spacing));
+                }
+                Row::Text(content, markers)
+            }
+            PreviewLine::Qr { align, data } => {
+                let label = format!("[QR {}]", data);
+                let used = text_width(&label).min(width);
+                let mut content = " ".repeat(align_offset(*align, used, width));
+                content.push_str(&label);
+                Row::Text(content, Vec::new())
+            }
+            PreviewLine::Barcode { align, data, .. } => {
+                let label = format!("[BARCODE {}]", data);
+                let used = text_width(&label).min(width);
+                let mut content = " ".repeat(align_offset(*align, used, width));
+                content.push_str(&label);
+                Row::Text(content, Vec::new())
+            }
+            PreviewLine::Cut(CutStyle::Full) => Row::Cut(" full cut "),
+            PreviewLine::Cut(CutStyle::Partial) => Row::Cut(" partial cut "),
+        })
+        .collect();
+
+    let frame = rows
+        .iter()
+        .map(|row| match row {
+            Row::Text(content, _) => text_width(content),
+            Row::Cut(_) => 0,
+        })
+        .fold(width, usize::max);
+
+    let mut out = String::new();
+    let border = format!("+{}+\n", "-".repeat(frame));
+    out.push_str(&border);
+    for row in rows {
+        match row {
+            Row::Text(content, markers) => {
+                out.push_str(&format!("|{}|", pad_right(&content, frame)));
+                if !markers.is_empty() {
+                    out.push_str(&format!(" {}", markers.join(" ")));
+                }
+                out.push('\n');
+            }
+            Row::Cut(label) => {
+                let side = frame.saturating_sub(label.len());
+                out.push_str(&format!(
+                    "+{}{}{}+\n",
+                    "~".repeat(side / 2),
+                    label,
+                    "~".repeat(side - side / 2)
+                ));
+            }
+        }
+    }
+    out.push_str(&border);
+    out
+}
+
+/// Number of QR modules per side for the data length (byte mode, error level M)
+fn qr_modules(len: usize) -> usize {
+    const CAPACITY: [usize; 10] = [14, 26, 42, 62, 84, 106, 122, 152, 180, 213];
+    let version = CAPACITY
+        .iter()
+        .position(|capacity| len <= *capacity)
+        .unwrap_or(CAPACITY.len() - 1)
+        + 1;
+    17 + version * 4
+}
+
+/// Module colour inside one of the three finder squares, or None outside them
+fn finder_module(row: usize, col: usize, modules: usize) -> Option<bool> {
+    let corners = [(0, 0), (0, modules - 7), (modules - 7, 0)];
+    corners.iter().find_map(|&(top, left)| {
+        if (top..top + 7).contains(&row) && (left..left + 7).contains(&col) {
+            let (r, c) = (row - top, col - left);
+            let ring = r.min(c).min(6 - r).min(6 - c);
+            Some(ring != 1)
+        } else {
+            None
+        }
+    })
+}
+
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>, // 8-bit grayscale, white background
+}
+
+impl Canvas {
+    fn fill(&mut self, x: usize, y: usize, w: usize, h: usize, shade: u8) {
+        for row in y..(y + h).min(self.height) {
+            for col in x..(x + w).min(self.width) {
+                self.pixels[row * self.width + col] = shade;
+            }
+        }
+    }
+}
+
+/// Tallest character cell on a text line
+fn text_height(segments: &[Segment]) -> usize {
+    segments
+        .iter()
+        .map(|segment| segment.style.cell_height())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Paper fed by a line; a line feed never moves less than the tallest character
+fn line_height(line: &PreviewLine) -> usize {
+    match line {
+        PreviewLine::Text {
+            segments,
+            line_spacing,
+            ..
+        } => (*line_spacing).max(text_height(segments)),
+        PreviewLine::Qr { data, .. } => qr_modules(data.len()) * QR_MODULE_DOTS + QR_GAP,
+        PreviewLine::Barcode { height, .. } => height + FONT_A.1 + QR_GAP, // Bars plus text below
+        PreviewLine::Cut(_) => CUT_HEIGHT,
+    }
+}
+
+/// Draw the lines at printer resolution.
+/// Glyphs are drawn as boxes (grey for regular, black for bold) and QR codes as
+/// a placeholder pattern of the right size, which is enough to judge the layout.
+fn render_png(
+    lines: &[PreviewLine],
+    line_dots: usize,
+    dots: usize,
+) -> Result<(Vec<u8>, usize, usize), String> {
+    let height = lines.iter().map(line_height).sum::<usize>().max(1);
+    let mut canvas = Canvas {
+        width: dots,
+        height,
+        pixels: vec![0xFF; dots * height],
+    };
+
+    let mut y = 0;
+    for line in lines {
+        let h = line_height(line);
+        match line {
+            PreviewLine::Text {
+                align, segments, ..
+            } => {
+                let baseline = y + text_height(segments);
+                let mut x = align_offset(*align, segments_dots(segments), line_dots);
+                for segment in segments {
+                    let style = &segment.style;
+                    let (background, glyph) = match (style.reverse, style.bold) {
+                        (false, false) => (0xFF, 0x80),
+                        (false, true) => (0xFF, 0x00),
+                        (true, false) => (0x00, 0xA0),
+                        (true, true) => (0x00, 0xFF),
+                    };
+                    let cell_h = style.cell_height();
+                    for c in segment.text.chars() {
+                        let advance = style.advance(c);
+                        if style.reverse {
+                            canvas.fill(x, baseline - cell_h, advance, cell_h, background);
+                        }
+                        let glyph_w = advance.saturating_sub(style.char_spacing * style.width);
+                        if !c.is_whitespace() && glyph_w > 0 {
+                            let inset_x = style.width;
+                            let inset_y = 3 * style.height;
+                            canvas.fill(
+                                x + inset_x,
+                                baseline - cell_h + inset_y,
+                                glyph_w.saturating_sub(2 * inset_x),
+                                cell_h.saturating_sub(2 * inset_y),
+                                glyph,
+                            );
+                        }
+                        if style.underline > 0 {
+                            canvas.fill(
+                                x,
+                                baseline.saturating_sub(style.underline),
+                                advance,
+                                style.underline,
+                                0x00,
+                            );
+                        }
+                        x += advance;
+                    }
+                }
+            }
+            PreviewLine::Qr { align, data } => {
+                let modules = qr_modules(data.len());
+                let size = modules * QR_MODULE_DOTS;
+                let x0 = align_offset(*align, size, dots);
+                // Deterministic pseudo-random modules seeded by the data
+                let mut seed = data.bytes().fold(0x811C_9DC5u32, |hash, b| {
+                    (hash ^ b as u32).wrapping_mul(0x0100_0193)
+                });
+                for row in 0..modules {
+                    for col in 0..modules {
+                        let dark = match finder_module(row, col, modules) {
+                            Some(dark) => dark,
+                            None => {
+                                seed ^= seed << 13;
+                                seed ^= seed >> 17;
+                                seed ^= seed << 5;
+                                seed & 1 == 1
+                            }
+                        };
+                        if dark {
+                            canvas.fill(
+                                x0 + col * QR_MODULE_DOTS,
+                                y + row * QR_MODULE_DOTS,
+                                QR_MODULE_DOTS,
+                                QR_MODULE_DOTS,
+                                0x00,
+                            );
+                        }
+                    }
+                }
+            }
+            PreviewLine::Barcode {
+                align,
+                data,
+                height,
+            } => {
+                // CODE128: 11 modules per symbol plus start, check and stop symbols
+                let modules = (data.len() + 3) * 11 + 2;
+                let size = modules * BARCODE_MODULE_DOTS;
+                let x0 = align_offset(*align, size, dots);
+                let mut seed = data.bytes().fold(0x811C_9DC5u32, |hash, b| {
+                    (hash ^ b as u32).wrapping_mul(0x0100_0193)
+                });
+                for module in 0..modules {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 17;
+                    seed ^= seed << 5;
+                    if module < 2 || module >= modules - 2 || seed & 1 == 1 {
+                        canvas.fill(
+                            x0 + module * BARCODE_MODULE_DOTS,
+                            y,
+                            BARCODE_MODULE_DOTS,
+                            *height,
+                            0x00,
+                        );
+                    }
+                }
+                // Human readable text under the bars
+                let text_x = align_offset(*align, data.len() * FONT_A.0, dots);
+                for (index, c) in data.chars().enumerate() {
+                    if !c.is_whitespace() {
+                        canvas.fill(
+                            text_x + index * FONT_A.0 + 1,
+                            y + height + 3,
+                            FONT_A.0 - 2,
+                            FONT_A.1 - 6,
+                            0x80,
+                        );
+                    }
+                }
+            }
+            PreviewLine::Cut(style) => {
+                let (dash, gap) = match style {
+                    CutStyle::Full => (12, 6),
+                    CutStyle::Partial => (4, 8),
+                };
+                let mut x = 0;
+                while x < dots {
+                    canvas.fill(x, y + h / 2 - 1, dash, 2, 0x00);
+                    x += dash + gap;
+                }
+            }
+        }
+        y += h;
+    }
+
+    let mut png_bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut png_bytes, dots as u32, height as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        writer
+            .write_image_data(&canvas.pixels)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok((png_bytes, dots, height))
+}
+
+/// Paper consumed by an ESC/POS byte stream
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PaperUsage {
+    pub lines: u64,
+    pub cuts: u64,
+    pub length_mm: f64,
+}
+
+/// Measure how much paper the printer will feed for a byte stream
+pub fn measure_paper(profile: &PrinterProfile, bytes: &[u8]) -> Result<PaperUsage, String> {
+    let lines = Interpreter::new(profile)?.run(bytes);
+    let mut usage = PaperUsage::default();
+    let mut dots = 0;
+    for line in &lines {
+        match line {
+            PreviewLine::Cut(_) => usage.cuts += 1,
+            _ => {
+                usage.lines += 1;
+                dots += line_height(line);
+            }
+        }
+    }
+    usage.length_mm = dots as f64 * 25.4 / profile.dpi.max(1) as f64;
+    Ok(usage)
+}
+
+/// Render a preview of an ESC/POS byte stream as the given printer would print it
+pub fn render_preview(profile: &PrinterProfile, bytes: &[u8]) -> Result<PrintPreview, String> {
+    let lines = Interpreter::new(profile)?.run(bytes);
+    let width = profile.chars_per_line as usize;
+    let dots = (profile.paper_width_mm as f64 * profile.dpi as f64 / 25.4) as usize;
+
+    let line_dots = width * FONT_A.0;
+    let text = render_text(&lines, width);
+    let (png_bytes, png_width, png_height) = render_png(&lines, line_dots, dots.max(line_dots))?;
+
+    Ok(PrintPreview {
+        text,
+        png_base64: BASE64.encode(png_bytes),
+        width: png_width as u32,
+        height: png_height as u32,
+    })
+}
+
+/// Preview a slip without printing it.
+/// Takes either the same command list as `print_with_options` or a raw ESC/POS byte stream.
+#[tauri::command]
+pub fn render_print_preview(
+    commands: Option<Vec<PrintCommand>>,
+    bytes: Option<Vec<u8>>,
+    profile: Option<String>,
+    logger: tauri::State<'_, Arc<Logger>>,
+    config: tauri::State<'_, Arc<ConfigStore>>,
+) -> Result<PrintPreview, String> {
+    let logger = Arc::clone(&logger);
+    logger.log(LogLevel::INFO, "Rendering print preview").ok();
+
+    let config = config.get();
+    let printer_port = config
+        .serialdata
+        .iter()
+        .find(|entry| entry.name == "PRINTER")
+        .map(|entry| entry.port.clone())
+        .unwrap_or_default();
+    let profile = resolve_printer_profile(&config, &printer_port, profile.as_deref())?;
+
+    let bytes = match (commands, bytes) {
+        (Some(commands), _) => encode_print_commands(&profile, &commands)?,
+        (None, Some(bytes)) => bytes,
+        (None, None) => return Err("Either commands or bytes must be provided".to_string()),
+    };
+
+    render_preview(&profile, &bytes)
+}
@@ -0,0 +1,251 @@
+use crate::config_store::ConfigStore;
+use crate::logger::{LogLevel, Logger};
+use crate::printer::{PrintCommand, PrintOptions};
+use crate::spooler::PrintSpooler;
+use crate::store::{get_config_dir, PrintHistorySettings};
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::State;
+
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const SUMMARY_TEXT_LINES: usize = 3; // Text commands shown in a summary
+
+/// A printed job kept for reprinting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrintHistoryEntry {
+    pub id: String,
+    pub port_name: String,
+    pub baud_rate: u32,
+    #[serde(default)]
+    pub profile: Option<String>,
+    pub commands: Vec<PrintCommand>,
+    pub summary: String,
+    pub printed_at: String,
+}
+
+/// What `list_print_history` shows staff: no slip contents, only a redacted summary
+#[derive(Debug, Clone, Serialize)]
+pub struct PrintHistoryItem {
+    id: String,
+    summary: String,
+    printed_at: String,
+}
+
+/// Bounded, expiring history of recently printed slips.
+/// Stored as one JSON file next to the config and pruned once a minute.
+pub struct PrintHistory {
+    entries: Mutex<Vec<PrintHistoryEntry>>,
+    config: Arc<ConfigStore>, // Limits are read on every use, so changes apply live
+    path: PathBuf,
+    logger: Arc<Logger>,
+}
+
+impl PrintHistory {
+    pub fn start(
+        config: &Arc<ConfigStore>,
+        logger: Arc<Logger>,
+    ) -> Result<Arc<Self>, Box<dyn std::error::Error>> {
+        let path = get_config_dir(Arc::clone(&logger))?.join("print_history.json");
+
+        let entries = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                logger
+                    .log(
+                        LogLevel::WARN,
+                        &format!("Discarding unreadable print history: {}", e),
+                    )
+                    .ok();
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+
+        let history = Arc::new(PrintHistory {
+            entries: Mutex::new(entries),
+            config: Arc::clone(config),
+            path,
+            logger,
+        });
+        history.prune();
+
+        let expiry = Arc::clone(&history);
+        thread::spawn(move || loop {
+            thread::sleep(EXPIRY_CHECK_INTERVAL);
+            expiry.prune();
+        });
+
+        Ok(history)
+    }
+
+    /// Remember a successfully printed job
+    pub fn record(&self, id: &str, options: &PrintOptions) {
+        let settings = self.settings();
+        if settings.max_entries == 0 {
+            return;
+        }
+
+        let entry = PrintHistoryEntry {
+            id: id.to_string(),
+            port_name: options.port_name.clone(),
+            baud_rate: options.baud_rate,
+            profile: options.profile.clone(),
+            commands: options.commands.clone(),
+            summary: summarize(&options.commands),
+            printed_at: Local::now().to_rfc3339(),
+        };
+
+        let mut entries = self.lock_entries();
+        entries.retain(|existing| existing.id != entry.id);
+        entries.push(entry);
+        let excess = entries.len().saturating_sub(settings.max_entries);
+        entries.drain(..excess);
+        self.save(&entries);
+    }
+
+    pub fn list(&self) -> Vec<PrintHistoryItem> {
+        self.prune();
+        self.lock_entries()
+            .iter()
+            .rev()
+            .map(|entry| PrintHistoryItem {
+                id: entry.id.clone(),
+                summary: entry.summary.clone(),
+                printed_at: entry.printed_at.clone(),
+            })
+            .collect()
+    }
+
+    pub fn get(&self, id: &str) -> Option<PrintHistoryEntry> {
+        self.prune();
+        self.lock_entries()
+            .iter()
+            .find(|entry| entry.id == id)
+            .cloned()
+    }
+
+    /// Delete entries older than the retention period
+    fn prune(&self) {
+        let cutoff =
+            Local::now() - ChronoDuration::minutes(self.settings().retention_minutes as i64);
+        let mut entries = self.lock_entries();
+        let before = entries.len();
+        entries.retain(|entry| {
+            DateTime::parse_from_rfc3339(&entry.printed_at)
+                .map(|printed_at| printed_at > cutoff)
+                .unwrap_or(false)
+        });
+        if entries.len() != before {
+            self.logger
+                .log(
+                    LogLevel::INFO,
+                    &format!("Expired {} print history entries", before - entries.len()),
+                )
+                .ok();
+            self.save(&entries);
+        }
+    }
+
+    fn save(&self, entries: &[PrintHistoryEntry]) {
+        let result = serde_json::to_string(entries)
+            .map_err(|e| e.to_string())
+            .and_then(|content| fs::write(&self.path, content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            self.logger
+                .log_error(
+                    &format!("Failed to save print history: {}", e),
+                    file!(),
+                    "save",
+                    line!(),
+                )
+                .ok();
+        }
+    }
+
+    fn settings(&self) -> PrintHistorySettings {
+        self.config.with(|config| config.print_history.clone())
+    }
+
+    fn lock_entries(&self) -> std::sync::MutexGuard<'_, Vec<PrintHistoryEntry>> {
+        match self.entries.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Mask all but the first character of every word so staff can recognise a slip
+/// without the history exposing names or student numbers
+fn redact(text: &str) -> String {
+    text.split(' ')
+        .map(|word| {
+            word.chars()
+                .enumerate()
+                .map(|(index, c)| if index == 0 || !c.is_alphanumeric() { c } else { '*' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn summarize(commands: &[PrintCommand]) -> String {
+    let texts: Vec<String> = commands
+        .iter()
+        .filter_map(|command| match command.commandType.as_str() {
+            "text" | "korean_text" | "wrap_text" | "fit_text" => command.value.clone(),
+            "two_column" => Some(format!(
+                "{} {}",
+                command.label.as_deref().unwrap_or(""),
+                command.value.as_deref().unwrap_or("")
+            )),
+            _ => None,
+        })
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+        .collect();
+
+    let mut summary: Vec<String> = texts
+        .iter()
+        .take(SUMMARY_TEXT_LINES)
+        .map(|text| redact(text))
+        .collect();
+    if texts.len() > SUMMARY_TEXT_LINES {
+        summary.push(format!("+{} more", texts.len() - SUMMARY_TEXT_LINES));
+    }
+    summary.join(" / ")
+}
+
+#[tauri::command]
+pub fn list_print_history(
+    history: State<'_, Arc<PrintHistory>>,
+) -> Result<Vec<PrintHistoryItem>, String> {
+    Ok(history.list())
+}
+
+/// Queue a slip from the history again; returns the new print job ID
+#[tauri::command]
+pub fn reprint(
+    job_id: String,
+    history: State<'_, Arc<PrintHistory>>,
+    spooler: State<'_, Arc<PrintSpooler>>,
+    logger: State<'_, Arc<Logger>>,
+) -> Result<String, String> {
+    let entry = history
+        .get(&job_id)
+        .ok_or_else(|| format!("Print job not in history: {}", job_id))?;
+
+    logger
+        .log(LogLevel::INFO, &format!("Reprinting job {}", job_id))
+        .ok();
+
+    spooler.submit(PrintOptions {
+        port_name: entry.port_name,
+        baud_rate: entry.baud_rate,
+        profile: entry.profile,
+        commands: entry.commands,
+    })
+}
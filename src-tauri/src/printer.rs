@@ -0,0 +1,692 @@
+use crate::config_store::ConfigStore;
+use crate::layout;
+use crate::logger::{LogLevel, Logger};
+use crate::paper::PaperTracker;
+use crate::preview::{measure_paper, PaperUsage};
+use crate::print_history::PrintHistory;
+use crate::store::{Config, CutStyle, PrinterProfile, PrinterTransport, SerialData};
+use crate::transport::{open_printer_output, PrinterOutput};
+use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrintOptions {
+    pub port_name: String,
+    pub baud_rate: u32,
+    #[serde(default)]
+    pub profile: Option<String>, // Printer profile name; resolved from the printer's SerialData when omitted
+    pub commands: Vec<PrintCommand>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrintCommand {
+    #[serde(rename = "type")]
+    pub commandType: String,
+    pub value: Option<String>, // Additional data for certain commands (e.g., alignment)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>, // Left column of `two_column`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rows: Option<Vec<Vec<String>>>, // Cells of `table`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub widths: Option<Vec<usize>>, // Column widths of `table`, split evenly when omitted
+}
+
+impl PrintCommand {
+    pub fn new(command_type: &str, value: Option<&str>) -> Self {
+        PrintCommand {
+            commandType: command_type.to_string(),
+            value: value.map(str::to_string),
+            label: None,
+            rows: None,
+            widths: None,
+        }
+    }
+
+    pub fn two_column(label: &str, value: &str) -> Self {
+        PrintCommand {
+            label: Some(label.to_string()),
+            ..PrintCommand::new("two_column", Some(value))
+        }
+    }
+}
+
+/// Why a print job did not complete
+#[derive(Debug, Clone)]
+pub enum PrintError {
+    /// The printer port could not be opened or written to; the job may succeed if retried
+    Port(String),
+    /// The command list itself is invalid; retrying will not help
+    Command(String),
+    /// The printer stopped taking data part way through the job
+    Write(WriteFailure),
+}
+
+/// How far a job got before sending it failed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriteFailure {
+    pub bytes_written: usize,
+    pub bytes_total: usize,
+    pub timed_out: bool, // The job deadline passed, as opposed to the port reporting an error
+    pub message: String,
+}
+
+impl PrintError {
+    /// Whether resending the whole job may succeed. A write that got part of the job to the
+    /// printer is not retried, as that would print a partial slip followed by a full one.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            PrintError::Port(_) => true,
+            PrintError::Write(failure) => failure.bytes_written == 0,
+            PrintError::Command(_) => false,
+        }
+    }
+
+    pub fn write_failure(&self) -> Option<&WriteFailure> {
+        match self {
+            PrintError::Write(failure) => Some(failure),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for PrintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrintError::Port(msg) | PrintError::Command(msg) => write!(f, "{}", msg),
+            PrintError::Write(failure) if failure.timed_out => write!(
+                f,
+                "Print job timed out after sending {} of {} bytes",
+                failure.bytes_written, failure.bytes_total
+            ),
+            PrintError::Write(failure) => write!(
+                f,
+                "Printer write failed after {} of {} bytes: {}",
+                failure.bytes_written, failure.bytes_total, failure.message
+            ),
+        }
+    }
+}
+
+/// What a completed job did to the printer
+#[derive(Debug, Clone)]
+pub struct PrintOutcome {
+    pub printer: String, // Port name, or profile name for printers not on a serial port
+    pub profile: PrinterProfile,
+    pub usage: PaperUsage,
+    pub paper_near_end: Option<bool>, // Roll sensor reading, if the printer reports one
+}
+
+/// The receipt printer entry of the serial port configuration
+pub fn find_printer(config: &Config) -> Result<&SerialData, String> {
+    config
+        .serialdata
+        .iter()
+        .find(|entry| entry.name == "PRINTER")
+        .ok_or_else(|| "No PRINTER entry in serialdata".to_string())
+}
+
+/// Pick the printer profile for a job.
+/// An explicitly requested profile wins, then the profile of the `SerialData`
+/// entry using the port, then the `PRINTER` entry, then the first configured profile.
+pub fn resolve_printer_profile(
+    config: &Config,
+    port_name: &str,
+    requested: Option<&str>,
+) -> Result<PrinterProfile, String> {
+    let profile_name = requested.map(str::to_string).or_else(|| {
+        config
+            .serialdata
+            .iter()
+            .find(|entry| entry.port == port_name && entry.profile.is_some())
+            .or_else(|| config.serialdata.iter().find(|entry| entry.name == "PRINTER"))
+            .and_then(|entry| entry.profile.clone())
+    });
+
+    match profile_name {
+        Some(name) => config
+            .printer_profiles
+            .iter()
+            .find(|profile| profile.name == name)
+            .cloned()
+            .ok_or_else(|| format!("Unknown printer profile: {}", name)),
+        None => config
+            .printer_profiles
+            .first()
+            .cloned()
+            .ok_or_else(|| "No printer profiles configured".to_string()),
+    }
+}
+
+/// Whether a print command type needs a value; None when the type is unknown
+pub fn command_requires_value(command_type: &str) -> Option<bool> {
+    match command_type {
+        "alignment" | "text" | "size" | "font" | "line_spacing" | "char_spacing"
+        | "korean_text" | "wrap_text" | "fit_text" | "barcode" | "qr_code" => Some(true),
+        "bold" | "unbold" | "normal_text" | "small_text" | "medium_text" | "large_text"
+        | "double_width" | "double_height" | "underline" | "no_underline" | "reverse"
+        | "no_reverse" | "two_column" | "table" | "blank_line" | "cut" | "full_cut"
+        | "partial_cut" | "clear_all" => Some(false),
+        _ => None,
+    }
+}
+
+/// Encode text with the profile's encoding, wrapped in its code page selection
+fn encode_profile_text(profile: &PrinterProfile, value: &str) -> Result<Vec<u8>, String> {
+    let encoding = Encoding::for_label(profile.encoding.as_bytes())
+        .ok_or_else(|| format!("Unsupported printer encoding: {}", profile.encoding))?;
+
+    let (encoded, _, had_errors) = encoding.encode(value);
+    if had_errors {
+        return Err(format!("Encoding failed for {} text", profile.encoding));
+    }
+
+    let mut bytes = Vec::with_capacity(encoded.len() + 6);
+    bytes.extend_from_slice(&[0x1B, 0x74, profile.code_page]); // ESC t n - select code page
+    bytes.extend_from_slice(encoded.as_ref());
+    bytes.extend_from_slice(&[0x1B, 0x74, profile.default_code_page]); // Restore default page
+    Ok(bytes)
+}
+
+/// Encode laid-out lines, one printer line each
+fn encode_profile_lines(profile: &PrinterProfile, lines: &[String]) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    for line in lines {
+        bytes.extend(encode_profile_text(profile, line)?);
+        bytes.push(b'\n');
+    }
+    Ok(bytes)
+}
+
+/// Feed the configured number of lines and cut the paper
+fn encode_cut(profile: &PrinterProfile, style: CutStyle) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    if profile.feed_lines_before_cut > 0 {
+        bytes.extend_from_slice(&[0x1B, b'd', profile.feed_lines_before_cut]); // ESC d n
+    }
+    match style {
+        CutStyle::Full => bytes.extend_from_slice(b"\x1DV\x00"), // GS V 0
+        CutStyle::Partial => bytes.extend_from_slice(b"\x1DV\x01"), // GS V 1
+    }
+    bytes
+}
+
+const FONT_A_DOTS: usize = 12;
+const FONT_B_DOTS: usize = 9;
+const MAX_SIZE_MULTIPLIER: u8 = 8;
+const BARCODE_HEIGHT: u8 = 80; // Bar height in dots
+
+// Pause before retrying a write the printer is too busy to take; doubles while it stays busy
+const WRITE_RETRY_MIN_DELAY: Duration = Duration::from_millis(20);
+const WRITE_RETRY_MAX_DELAY: Duration = Duration::from_millis(500);
+
+/// Text settings that change how many characters fit on a line
+struct TextState {
+    width: u8,
+    height: u8,
+    font_b: bool,
+    char_spacing: usize,
+}
+
+impl Default for TextState {
+    fn default() -> Self {
+        TextState {
+            width: 1,
+            height: 1,
+            font_b: false,
+            char_spacing: 0,
+        }
+    }
+}
+
+impl TextState {
+    /// Columns available to the layout commands with the current font, size and spacing
+    fn line_width(&self, profile: &PrinterProfile) -> usize {
+        let font_dots = if self.font_b { FONT_B_DOTS } else { FONT_A_DOTS };
+        let line_dots = profile.chars_per_line as usize * FONT_A_DOTS;
+        (line_dots / ((font_dots + self.char_spacing) * self.width as usize)).max(1)
+    }
+
+    /// GS ! n - character width and height multipliers (1-8)
+    fn set_size(&mut self, width: u8, height: u8) -> [u8; 3] {
+        self.width = width;
+        self.height = height;
+        [0x1D, b'!', ((width - 1) << 4) | (height - 1)]
+    }
+}
+
+fn parse_size(value: &str) -> Result<(u8, u8), String> {
+    let invalid = || format!("Invalid size value: {} (expected WxH with 1-8)", value);
+    let (width, height) = value.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width: u8 = width.trim().parse().map_err(|_| invalid())?;
+    let height: u8 = height.trim().parse().map_err(|_| invalid())?;
+    if !(1..=MAX_SIZE_MULTIPLIER).contains(&width) || !(1..=MAX_SIZE_MULTIPLIER).contains(&height) {
+        return Err(invalid());
+    }
+    Ok((width, height))
+}
+
+fn parse_u8(value: &str, command: &str) -> Result<u8, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid {} value: {} (expected 0-255)", command, value))
+}
+
+/// Translate the command list into the ESC/POS byte stream sent to the printer
+pub fn encode_print_commands(
+    profile: &PrinterProfile,
+    commands: &[PrintCommand],
+) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+
+    // Set the print area width from the paper width and head resolution
+    let dots = (profile.paper_width_mm as f64 * profile.dpi as f64 / 25.4) as u16;
+    let nL = (dots % 256) as u8;
+    let nH = (dots / 256) as u8;
+    bytes.extend_from_slice(&[0x1D, 0x57, nL, nH]);
+
+    let mut state = TextState::default();
+
+    for command in commands {
+        let line_width = state.line_width(profile);
+
+        match command.commandType.as_str() {
+            "bold" => {
+                bytes.extend_from_slice(b"\x1BE\x01"); // Command to enable bold text
+            }
+            "unbold" => {
+                bytes.extend_from_slice(b"\x1BE\x00"); // Command to disable bold text
+            }
+            "alignment" => {
+                if let Some(value) = command.value.as_deref() {
+                    let alignment_command: &[u8] = match value {
+                        "left" => b"\x1Ba\x00",
+                        "center" => b"\x1Ba\x01",
+                        "right" => b"\x1Ba\x02",
+                        _ => return Err(format!("Invalid alignment value: {}", value)),
+                    };
+                    bytes.extend_from_slice(alignment_command);
+                }
+            }
+            "text" => {
+                if let Some(value) = command.value.as_deref() {
+                    if value.is_ascii() {
+                        bytes.extend_from_slice(value.as_bytes());
+                    } else {
+                        // Encoded like korean_text; the printer can't print raw UTF-8
+                        bytes.extend(encode_profile_text(profile, value)?);
+                    }
+                } else {
+                    return Err("Missing text value for text command".to_string());
+                }
+            }
+            "normal_text" => {
+                bytes.extend(state.set_size(1, 1)); // Command for normal text size
+            }
+            "small_text" => {
+                bytes.extend(state.set_size(1, 2)); // Command for small text size
+            }
+            "medium_text" => {
+                bytes.extend(state.set_size(2, 2)); // Command for medium text size
+            }
+            "large_text" => {
+                bytes.extend(state.set_size(3, 3)); // Command for large text size
+            }
+            "double_width" => {
+                bytes.extend(state.set_size(2, 1));
+            }
+            "double_height" => {
+                bytes.extend(state.set_size(1, 2));
+            }
+            "size" => {
+                // Width x height multipliers, e.g. "2x3"
+                let value = command.value.as_deref().ok_or("Missing value for size command")?;
+                let (width, height) = parse_size(value)?;
+                bytes.extend(state.set_size(width, height));
+            }
+            "underline" => {
+                // Optional thickness: 1 (default) or 2 dots
+                let thickness = match command.value.as_deref() {
+                    None | Some("1") => 1,
+                    Some("2") => 2,
+                    Some(value) => return Err(format!("Invalid underline value: {}", value)),
+                };
+                bytes.extend_from_slice(&[0x1B, b'-', thickness]); // ESC - n
+            }
+            "no_underline" => {
+                bytes.extend_from_slice(b"\x1B-\x00");
+            }
+            "reverse" => {
+                bytes.extend_from_slice(b"\x1DB\x01"); // GS B 1 - white on black
+            }
+            "no_reverse" => {
+                bytes.extend_from_slice(b"\x1DB\x00");
+            }
+            "font" => {
+                state.font_b = match command.value.as_deref() {
+                    Some("A") | Some("a") => false,
+                    Some("B") | Some("b") => true,
+                    other => return Err(format!("Invalid font value: {:?}", other)),
+                };
+                bytes.extend_from_slice(&[0x1B, b'M', state.font_b as u8]); // ESC M n
+            }
+            "line_spacing" => {
+                // Line feed amount in dots, or "default"
+                match command.value.as_deref() {
+                    None | Some("default") => bytes.extend_from_slice(b"\x1B2"), // ESC 2
+                    Some(value) => {
+                        let dots = parse_u8(value, "line_spacing")?;
+                        bytes.extend_from_slice(&[0x1B, b'3', dots]); // ESC 3 n
+                    }
+                }
+            }
+            "char_spacing" => {
+                // Extra dots to the right of every character
+                let dots = parse_u8(command.value.as_deref().unwrap_or("0"), "char_spacing")?;
+                state.char_spacing = dots as usize;
+                bytes.extend_from_slice(&[0x1B, b' ', dots]); // ESC SP n
+            }
+            "korean_text" => {
+                if let Some(value) = command.value.as_deref() {
+                    bytes.extend(encode_profile_text(profile, value)?);
+                } else {
+                    return Err("Missing text value for Korean text command".to_string());
+                }
+            }
+            "wrap_text" => {
+                // Word-wrapped to the printer's line width
+                let value = command
+                    .value
+                    .as_deref()
+                    .ok_or("Missing text value for wrap_text command")?;
+                bytes.extend(encode_profile_lines(profile, &layout::wrap(value, line_width))?);
+            }
+            "fit_text" => {
+                // A single line, cut with an ellipsis when too long
+                let value = command
+                    .value
+                    .as_deref()
+                    .ok_or("Missing text value for fit_text command")?;
+                bytes.extend(encode_profile_lines(
+                    profile,
+                    &[layout::truncate(value, line_width)],
+                )?);
+            }
+            "two_column" => {
+                let label = command.label.as_deref().unwrap_or("");
+                let value = command.value.as_deref().unwrap_or("");
+                bytes.extend(encode_profile_lines(
+                    profile,
+                    &layout::two_column(label, value, line_width),
+                )?);
+            }
+            "table" => {
+                let rows = command
+                    .rows
+                    .as_ref()
+                    .ok_or("Missing rows for table command")?;
+                bytes.extend(encode_profile_lines(
+                    profile,
+                    &layout::table(rows, command.widths.as_deref(), line_width),
+                )?);
+            }
+            "blank_line" => {
+                // Command to print a blank line
+                bytes.push(b'\n'); // Assuming a newline is sufficient for a blank line
+            }
+            "cut" => {
+                // Cut in the style configured for this printer model
+                bytes.extend(encode_cut(profile, profile.cut));
+            }
+            "full_cut" => {
+                bytes.extend(encode_cut(profile, CutStyle::Full));
+            }
+            "partial_cut" => {
+                bytes.extend(encode_cut(profile, CutStyle::Partial));
+            }
+            "clear_all" => {
+                // Command to clear all settings (example command, adjust as needed)
+                bytes.extend_from_slice(b"\x1B\x40"); // Example command to reset printer settings
+                state = TextState::default();
+            }
+            "barcode" => {
+                // CODE128 with the human readable text printed below
+                let value = command
+                    .value
+                    .as_deref()
+                    .ok_or("Missing value for barcode command")?;
+                if value.is_empty()
+                    || value.len() > 253
+                    || !value.bytes().all(|b| (0x20..0x7F).contains(&b))
+                {
+                    return Err(format!("Invalid barcode value: {}", value));
+                }
+                bytes.extend_from_slice(b"\x1DH\x02"); // GS H 2 - text below the bars
+                bytes.extend_from_slice(&[0x1D, b'h', BARCODE_HEIGHT]); // GS h n - bar height
+                bytes.extend_from_slice(b"\x1Dw\x02"); // GS w 2 - module width
+                bytes.extend_from_slice(&[0x1D, b'k', 73, value.len() as u8 + 2]); // GS k 73 n
+                bytes.extend_from_slice(b"{B"); // Code set B
+                bytes.extend_from_slice(value.as_bytes());
+                bytes.push(b'\n');
+            }
+            "qr_code" => {
+                if let Some(value) = command.value.as_deref() {
+                    if value.len() > 230 {
+                        // Adjust based on your QR code data capacity needs
+                        return Err("Data too long for QR code capacity".to_string());
+                    }
+
+                    // Center alignment for CODE type
+                    bytes.extend_from_slice(b"\x1Ba\x01"); // ESC a 1 (Center Alignment)
+
+                    // Settings (Using example values, replace with actual settings if needed)
+                    let margin: u8 = 0; // Example margin (multiplied by 8 for mm conversion)
+
+                    // Margin setting for the printer
+                    let margin_mm = margin * 8;
+                    bytes.extend_from_slice(&[0x1D, b'L', margin_mm, 0x00]);
+
+                    // Check for 2D barcode (QR Code) and append the corresponding command
+                    if !value.is_empty() {
+                        bytes.push(0x1A);
+                        bytes.push(b'B');
+                        bytes.push(2); // Constant indicating QR Code in your Node.js example
+                        bytes.push(value.len() as u8); // Length of the data to be encoded
+                        bytes.push(5); // Placeholder, adjust if necessary
+                        bytes.extend_from_slice(value.as_bytes());
+                        bytes.push(0x00); // Null terminator for string
+                        bytes.push(b'\n'); // New line if needed (check your printer's requirements)
+                    }
+                } else {
+                    return Err("Missing value for QR code command".to_string());
+                }
+            }
+            // Add other commands like "small_text", "barcode" here as in the original code.
+            _ => {
+                return Err(format!("Unsupported command type: {}", command.commandType));
+            }
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Encode and send one print job to the printer.
+/// The commands are encoded before the port is opened so that an invalid job
+/// never leaves a half-printed slip behind.
+pub fn run_print_job(
+    config: &Config,
+    port_name: &str,
+    baud_rate: u32,
+    profile_name: Option<&str>,
+    commands: &[PrintCommand],
+    logger: &Arc<Logger>,
+) -> Result<PrintOutcome, PrintError> {
+    let profile =
+        resolve_printer_profile(config, port_name, profile_name).map_err(PrintError::Command)?;
+
+    let bytes = encode_print_commands(&profile, commands).map_err(|e| {
+        logger
+            .log_error(
+                &format!("Invalid print job: {}", e),
+                file!(),
+                "run_print_job",
+                line!(),
+            )
+            .ok();
+        PrintError::Command(e)
+    })?;
+
+    let mut output = open_printer_output(&profile.transport, port_name, baud_rate, logger)
+        .map_err(|e| {
+            logger
+                .log_error(&e, file!(), "run_print_job", line!())
+                .ok();
+            PrintError::Port(e)
+        })?;
+
+    write_to_printer(&mut *output, &bytes, &profile).map_err(|failure| {
+        let error = PrintError::Write(failure);
+        logger
+            .log_error(&error.to_string(), file!(), "run_print_job", line!())
+            .ok();
+        error
+    })?;
+
+    let usage = measure_paper(&profile, &bytes).unwrap_or_default();
+    let paper_near_end = output.paper_near_end();
+    let printer = match profile.transport {
+        PrinterTransport::Serial => port_name.to_string(),
+        _ => profile.name.clone(),
+    };
+
+    Ok(PrintOutcome {
+        printer,
+        profile,
+        usage,
+        paper_near_end,
+    })
+}
+
+#[tauri::command]
+pub fn print_with_options(
+    print_options: PrintOptions,
+    logger: tauri::State<'_, Arc<Logger>>,
+    config: tauri::State<'_, Arc<ConfigStore>>,
+    history: tauri::State<'_, Arc<PrintHistory>>,
+    paper: tauri::State<'_, Arc<PaperTracker>>,
+) -> Result<(), String> {
+    let logger = Arc::clone(&logger);
+    logger.log(LogLevel::INFO, "Starting print job").ok();
+
+    let outcome = run_print_job(
+        &config.get(),
+        &print_options.port_name,
+        print_options.baud_rate,
+        print_options.profile.as_deref(),
+        &print_options.commands,
+        &logger,
+    )
+    .map_err(|e| e.to_string())?;
+
+    history.record(&Uuid::new_v4().to_string(), &print_options);
+    paper.record(&outcome);
+    Ok(())
+}
+
+/// Send the whole job in chunks no larger than the printer's receive buffer.
+/// Short writes and port timeouts are retried until the job deadline passes;
+/// after a timeout the next attempt waits a little longer.
+fn write_to_printer(
+    port: &mut dyn PrinterOutput,
+    data: &[u8],
+    profile: &PrinterProfile,
+) -> Result<(), WriteFailure> {
+    let deadline = Instant::now() + Duration::from_secs(profile.job_timeout_secs);
+    let chunk_delay = Duration::from_millis(profile.write_chunk_delay_ms);
+    let mut written = 0;
+    let mut retry_delay = WRITE_RETRY_MIN_DELAY;
+
+    let failure = |written: usize, timed_out: bool, message: String| WriteFailure {
+        bytes_written: written,
+        bytes_total: data.len(),
+        timed_out,
+        message,
+    };
+
+    for chunk in data.chunks(profile.write_chunk_bytes.max(1)) {
+        let mut sent = 0;
+        while sent < chunk.len() {
+            if Instant::now() >= deadline {
+                return Err(failure(written, true, "job deadline passed".to_string()));
+            }
+            match port.write(&chunk[sent..]) {
+                Ok(0) => {
+                    return Err(failure(
+                        written,
+                        false,
+                        "printer accepted no data".to_string(),
+                    ))
+                }
+                Ok(n) => {
+                    sent += n;
+                    written += n;
+                    retry_delay = WRITE_RETRY_MIN_DELAY;
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                // The printer is busy or its buffer is full; keep trying until the deadline
+                Err(e) if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => {
+                    thread::sleep(
+                        retry_delay.min(deadline.saturating_duration_since(Instant::now())),
+                    );
+                    retry_delay = (retry_delay * 2).min(WRITE_RETRY_MAX_DELAY);
+                }
+                Err(e) => return Err(failure(written, false, e.to_string())),
+            }
+        }
+
+        port.flush()
+            .map_err(|e| failure(written, false, format!("flush failed: {}", e)))?;
+        if written < data.len() && !chunk_delay.is_zero() {
+            thread::sleep(chunk_delay.min(deadline.saturating_duration_since(Instant::now())));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::default_config;
+
+    fn profile() -> PrinterProfile {
+        default_config().printer_profiles.remove(0)
+    }
+
+    #[test]
+    fn text_sends_ascii_as_is() {
+        let bytes =
+            encode_print_commands(&profile(), &[PrintCommand::new("text", Some("Seat 112"))])
+                .unwrap();
+        assert!(bytes.windows(8).any(|window| window == b"Seat 112"));
+        assert!(!bytes.windows(2).any(|window| window == [0x1B, 0x74]));
+    }
+
+    #[test]
+    fn text_encodes_non_ascii_like_korean_text() {
+        let profile = profile();
+        let encode = |command_type| {
+            encode_print_commands(&profile, &[PrintCommand::new(command_type, Some("열람실 112"))])
+                .unwrap()
+        };
+        let bytes = encode("text");
+        assert_eq!(bytes, encode("korean_text"));
+        assert!(!bytes.windows(3).any(|window| window == "열".as_bytes()));
+    }
+}
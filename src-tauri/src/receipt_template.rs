@@ -0,0 +1,812 @@
+//! Receipt templates kept as `<name>.tmpl` files in the `templates` folder of the config directory.
+//!
+//! One print command per line, written as `<command type> <value>`. Values may hold
+//! `{{ path }}` placeholders filled from the data passed to `print_template`.
+//! On top of the print commands a template can use:
+//!
+//! ```text
+//! # comment
+//! two_column Label | {{ value }}
+//! row {{ a }} | {{ b }}         consecutive rows are printed as one table
+//! if [not] path ... [else ...] end
+//! each path as item ... end     `item` and `item.field` are in scope inside the loop
+//! ```
+
+use crate::config_store::ConfigStore;
+use crate::logger::{LogLevel, Logger};
+use crate::printer::{command_requires_value, find_printer, PrintCommand, PrintOptions};
+use crate::spooler::PrintSpooler;
+use crate::store::get_config_dir;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::State;
+
+const TEMPLATE_EXTENSION: &str = "tmpl";
+const CELL_SEPARATOR: char = '|';
+
+/// A problem in a template file, located by line and, where known, column (both 1-based)
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateError {
+    line: usize,
+    column: Option<usize>,
+    message: String,
+}
+
+impl TemplateError {
+    fn new(line: usize, column: Option<usize>, message: impl Into<String>) -> Self {
+        TemplateError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {}: {}", self.line, column, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+/// What `list_print_templates` reports for every template file
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateInfo {
+    name: String,
+    errors: Vec<TemplateError>,
+}
+
+#[derive(Debug, Clone)]
+enum Part {
+    Literal(String),
+    Placeholder(String),
+}
+
+/// A value with its placeholders split out
+#[derive(Debug, Clone)]
+struct Text {
+    parts: Vec<Part>,
+    column: usize, // Where the value starts on its line, for render errors
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Command {
+        line: usize,
+        command_type: String,
+        value: Option<Text>,
+    },
+    TwoColumn {
+        line: usize,
+        label: Text,
+        value: Text,
+    },
+    Row {
+        line: usize,
+        cells: Vec<Text>,
+    },
+    If {
+        negate: bool,
+        path: String,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Each {
+        line: usize,
+        path: String,
+        item: String,
+        body: Vec<Node>,
+    },
+}
+
+/// A parsed, validated template
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+// The keyword closing a block, and the line it was found on
+enum BlockEnd {
+    Else(usize),
+    End(usize),
+    Eof,
+}
+
+struct Parser<'a> {
+    lines: Vec<(usize, &'a str)>,
+    pos: usize,
+    errors: Vec<TemplateError>,
+}
+
+impl Template {
+    /// Parse and validate a template; every problem found is reported, not just the first
+    pub fn parse(source: &str) -> Result<Template, Vec<TemplateError>> {
+        let mut parser = Parser {
+            lines: source
+                .lines()
+                .enumerate()
+                .map(|(index, line)| (index + 1, line))
+                .collect(),
+            pos: 0,
+            errors: Vec::new(),
+        };
+
+        let (nodes, mut end) = parser.block();
+        // Keep checking the lines after a stray keyword
+        loop {
+            match end {
+                BlockEnd::Else(line) => {
+                    parser
+                        .errors
+                        .push(TemplateError::new(line, None, "'else' without 'if'"))
+                }
+                BlockEnd::End(line) => parser.errors.push(TemplateError::new(
+                    line,
+                    None,
+                    "'end' without 'if' or 'each'",
+                )),
+                BlockEnd::Eof => break,
+            }
+            end = parser.block().1;
+        }
+
+        parser.errors.sort_by_key(|error| error.line);
+        if parser.errors.is_empty() {
+            Ok(Template { nodes })
+        } else {
+            Err(parser.errors)
+        }
+    }
+
+    /// Fill in the data and produce the print commands
+    pub fn render(&self, data: &Value) -> Result<Vec<PrintCommand>, TemplateError> {
+        let mut renderer = Renderer {
+            scopes: Vec::new(),
+            data,
+            commands: Vec::new(),
+            rows: Vec::new(),
+        };
+        renderer.nodes(&self.nodes)?;
+        renderer.flush_rows();
+        Ok(renderer.commands)
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn block(&mut self) -> (Vec<Node>, BlockEnd) {
+        let mut nodes = Vec::new();
+
+        while let Some(&(line_no, raw)) = self.lines.get(self.pos) {
+            self.pos += 1;
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let indent = raw.len() - raw.trim_start().len();
+            let (keyword, rest) = match line.split_once(char::is_whitespace) {
+                Some((keyword, rest)) => (keyword, rest.trim()),
+                None => (line, ""),
+            };
+            // Column of the first character after the keyword
+            let rest_column = indent + line.len() - rest.len() + 1;
+
+            match keyword {
+                "else" => return (nodes, BlockEnd::Else(line_no)),
+                "end" => return (nodes, BlockEnd::End(line_no)),
+                "if" => {
+                    let (negate, path) = match rest.strip_prefix("not ") {
+                        Some(path) => (true, path.trim()),
+                        None => (false, rest),
+                    };
+                    self.check_path(path, line_no, rest_column);
+
+                    let (then, end) = self.block();
+                    let otherwise = match end {
+                        BlockEnd::Else(_) => {
+                            let (otherwise, end) = self.block();
+                            self.expect_end(end, line_no, "if");
+                            otherwise
+                        }
+                        end => {
+                            self.expect_end(end, line_no, "if");
+                            Vec::new()
+                        }
+                    };
+                    nodes.push(Node::If {
+                        negate,
+                        path: path.to_string(),
+                        then,
+                        otherwise,
+                    });
+                }
+                "each" => {
+                    let (path, item) = match rest.split_once(" as ") {
+                        Some((path, item)) => (path.trim(), item.trim()),
+                        None => {
+                            self.errors.push(TemplateError::new(
+                                line_no,
+                                Some(rest_column),
+                                "expected 'each <list> as <name>'",
+                            ));
+                            (rest, "item")
+                        }
+                    };
+                    self.check_path(path, line_no, rest_column);
+                    if item.is_empty() || item.contains(|c: char| !is_name_char(c)) {
+                        self.errors.push(TemplateError::new(
+                            line_no,
+                            Some(rest_column),
+                            format!("invalid loop variable name '{}'", item),
+                        ));
+                    }
+
+                    let (body, end) = self.block();
+                    self.expect_end(end, line_no, "each");
+                    nodes.push(Node::Each {
+                        line: line_no,
+                        path: path.to_string(),
+                        item: item.to_string(),
+                        body,
+                    });
+                }
+                "two_column" => {
+                    let cells = self.cells(rest, line_no, rest_column);
+                    if cells.len() != 2 {
+                        self.errors.push(TemplateError::new(
+                            line_no,
+                            Some(rest_column),
+                            "expected 'two_column <label> | <value>'",
+                        ));
+                        continue;
+                    }
+                    let mut cells = cells.into_iter();
+                    nodes.push(Node::TwoColumn {
+                        line: line_no,
+                        label: cells.next().unwrap(),
+                        value: cells.next().unwrap(),
+                    });
+                }
+                "row" => {
+                    let cells = self.cells(rest, line_no, rest_column);
+                    nodes.push(Node::Row {
+                        line: line_no,
+                        cells,
+                    });
+                }
+                "table" => self.errors.push(TemplateError::new(
+                    line_no,
+                    Some(indent + 1),
+                    "use 'row' lines to print a table",
+                )),
+                command_type => match command_requires_value(command_type) {
+                    None => self.errors.push(TemplateError::new(
+                        line_no,
+                        Some(indent + 1),
+                        format!("unknown command '{}'", command_type),
+                    )),
+                    Some(true) if rest.is_empty() => self.errors.push(TemplateError::new(
+                        line_no,
+                        Some(indent + 1),
+                        format!("'{}' needs a value", command_type),
+                    )),
+                    Some(_) => {
+                        let value = if rest.is_empty() {
+                            None
+                        } else {
+                            self.text(rest, line_no, rest_column)
+                        };
+                        nodes.push(Node::Command {
+                            line: line_no,
+                            command_type: command_type.to_string(),
+                            value,
+                        });
+                    }
+                },
+            }
+        }
+
+        (nodes, BlockEnd::Eof)
+    }
+
+    fn expect_end(&mut self, end: BlockEnd, opened_at: usize, keyword: &str) {
+        match end {
+            BlockEnd::End(_) => {}
+            BlockEnd::Else(line) => self.errors.push(TemplateError::new(
+                line,
+                None,
+                format!("unexpected 'else' in '{}' block", keyword),
+            )),
+            BlockEnd::Eof => self.errors.push(TemplateError::new(
+                opened_at,
+                None,
+                format!("'{}' is never closed with 'end'", keyword),
+            )),
+        }
+    }
+
+    fn check_path(&mut self, path: &str, line: usize, column: usize) {
+        if path.is_empty()
+            || !path
+                .split('.')
+                .all(|key| !key.is_empty() && key.chars().all(is_name_char))
+        {
+            self.errors.push(TemplateError::new(
+                line,
+                Some(column),
+                format!("invalid data path '{}'", path),
+            ));
+        }
+    }
+
+    fn cells(&mut self, value: &str, line: usize, column: usize) -> Vec<Text> {
+        let mut cells = Vec::new();
+        let mut start = 0;
+        for cell in value.split(CELL_SEPARATOR) {
+            let trimmed = cell.trim_start();
+            let cell_column = column + value[..start].chars().count() + cell.chars().count()
+                - trimmed.chars().count();
+            if let Some(text) = self.text(trimmed.trim_end(), line, cell_column) {
+                cells.push(text);
+            }
+            start += cell.len() + CELL_SEPARATOR.len_utf8();
+        }
+        cells
+    }
+
+    /// Split a value into literals and `{{ path }}` placeholders
+    fn text(&mut self, value: &str, line: usize, column: usize) -> Option<Text> {
+        let mut parts = Vec::new();
+        let mut rest = value;
+        let mut offset = 0; // Characters consumed so far, for error columns
+
+        while let Some(open) = rest.find("{{") {
+            if open > 0 {
+                parts.push(Part::Literal(rest[..open].to_string()));
+            }
+            let placeholder_column = column + offset + rest[..open].chars().count();
+            let after = &rest[open + 2..];
+            let Some(close) = after.find("}}") else {
+                self.errors.push(TemplateError::new(
+                    line,
+                    Some(placeholder_column),
+                    "unclosed placeholder, expected '}}'",
+                ));
+                return None;
+            };
+            let path = after[..close].trim();
+            if path.is_empty()
+                || !path
+                    .split('.')
+                    .all(|key| !key.is_empty() && key.chars().all(is_name_char))
+            {
+                self.errors.push(TemplateError::new(
+                    line,
+                    Some(placeholder_column),
+                    format!("invalid placeholder '{{{{{}}}}}'", &after[..close]),
+                ));
+                return None;
+            }
+            parts.push(Part::Placeholder(path.to_string()));
+
+            let consumed = &rest[..open + 2 + close + 2];
+            offset += consumed.chars().count();
+            rest = &rest[open + 2 + close + 2..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Literal(rest.to_string()));
+        }
+
+        Some(Text { parts, column })
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+struct Renderer<'a> {
+    scopes: Vec<(String, Value)>, // Loop variables, innermost last
+    data: &'a Value,
+    commands: Vec<PrintCommand>,
+    rows: Vec<Vec<String>>, // Rows waiting to be printed as one table
+}
+
+impl Renderer<'_> {
+    fn nodes(&mut self, nodes: &[Node]) -> Result<(), TemplateError> {
+        for node in nodes {
+            match node {
+                Node::Command {
+                    line,
+                    command_type,
+                    value,
+                } => {
+                    let value = match value {
+                        Some(text) => Some(self.text(text, *line)?),
+                        None => None,
+                    };
+                    self.push(PrintCommand::new(command_type, value.as_deref()));
+                }
+                Node::TwoColumn { line, label, value } => {
+                    let label = self.text(label, *line)?;
+                    let value = self.text(value, *line)?;
+                    self.push(PrintCommand::two_column(&label, &value));
+                }
+                Node::Row { line, cells } => {
+                    let row = cells
+                        .iter()
+                        .map(|cell| self.text(cell, *line))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    self.rows.push(row);
+                }
+                Node::If {
+                    negate,
+                    path,
+                    then,
+                    otherwise,
+                    ..
+                } => {
+                    if is_truthy(self.lookup(path)) != *negate {
+                        self.nodes(then)?;
+                    } else {
+                        self.nodes(otherwise)?;
+                    }
+                }
+                Node::Each {
+                    line,
+                    path,
+                    item,
+                    body,
+                } => {
+                    let items = match self.lookup(path) {
+                        Some(Value::Array(items)) => items.clone(),
+                        None | Some(Value::Null) => Vec::new(),
+                        Some(_) => {
+                            return Err(TemplateError::new(
+                                *line,
+                                None,
+                                format!("'{}' is not a list", path),
+                            ))
+                        }
+                    };
+                    for value in items {
+                        self.scopes.push((item.clone(), value));
+                        let result = self.nodes(body);
+                        self.scopes.pop();
+                        result?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, command: PrintCommand) {
+        self.flush_rows();
+        self.commands.push(command);
+    }
+
+    fn flush_rows(&mut self) {
+        if !self.rows.is_empty() {
+            self.commands.push(PrintCommand {
+                rows: Some(std::mem::take(&mut self.rows)),
+                ..PrintCommand::new("table", None)
+            });
+        }
+    }
+
+    fn lookup(&self, path: &str) -> Option<&Value> {
+        let mut keys = path.split('.');
+        let first = keys.next()?;
+        let root = self
+            .scopes
+            .iter()
+            .rev()
+            .find(|(name, _)| name == first)
+            .map(|(_, value)| value)
+            .or_else(|| self.data.get(first))?;
+        keys.try_fold(root, |value, key| match value {
+            Value::Array(items) => key.parse::<usize>().ok().and_then(|index| items.get(index)),
+            _ => value.get(key),
+        })
+    }
+
+    fn text(&self, text: &Text, line: usize) -> Result<String, TemplateError> {
+        let mut out = String::new();
+        for part in &text.parts {
+            match part {
+                Part::Literal(literal) => out.push_str(literal),
+                Part::Placeholder(path) => match self.lookup(path) {
+                    Some(Value::String(value)) => out.push_str(value),
+                    Some(Value::Null) => {}
+                    Some(value @ (Value::Number(_) | Value::Bool(_))) => {
+                        out.push_str(&value.to_string())
+                    }
+                    Some(_) => {
+                        return Err(TemplateError::new(
+                            line,
+                            Some(text.column),
+                            format!("'{}' is a list or object and can't be printed", path),
+                        ))
+                    }
+                    None => {
+                        return Err(TemplateError::new(
+                            line,
+                            Some(text.column),
+                            format!("no data for '{}'", path),
+                        ))
+                    }
+                },
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn is_truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => false,
+        Some(Value::Bool(value)) => *value,
+        Some(Value::String(value)) => !value.is_empty(),
+        Some(Value::Array(items)) => !items.is_empty(),
+        Some(Value::Number(number)) => number.as_f64() != Some(0.0),
+        Some(Value::Object(_)) => true,
+    }
+}
+
+fn get_templates_dir(logger: &Arc<Logger>) -> Result<PathBuf, String> {
+    let dir = get_config_dir(Arc::clone(logger))
+        .map_err(|e| e.to_string())?
+        .join("templates");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create templates directory: {}", e))?;
+    Ok(dir)
+}
+
+fn template_path(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    if name.is_empty() || name.contains(|c: char| !is_name_char(c)) {
+        return Err(format!("Invalid template name: {}", name));
+    }
+    Ok(dir.join(name).with_extension(TEMPLATE_EXTENSION))
+}
+
+/// Read and validate one template from the templates directory
+pub fn load_template(name: &str, logger: &Arc<Logger>) -> Result<Template, String> {
+    let path = template_path(&get_templates_dir(logger)?, name)?;
+    let source = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read template {}: {}", path.display(), e))?;
+
+    Template::parse(&source).map_err(|errors| {
+        let message = errors
+            .iter()
+            .map(|error| format!("{}.{} {}", name, TEMPLATE_EXTENSION, error))
+            .collect::<Vec<_>>()
+            .join("\n");
+        logger
+            .log_error(
+                &format!("Invalid template:\n{}", message),
+                file!(),
+                "load_template",
+                line!(),
+            )
+            .ok();
+        message
+    })
+}
+
+/// List the templates in the config directory together with any validation errors
+#[tauri::command]
+pub fn list_print_templates(logger: State<'_, Arc<Logger>>) -> Result<Vec<TemplateInfo>, String> {
+    let logger = Arc::clone(&logger);
+    let dir = get_templates_dir(&logger)?;
+    let entries = fs::read_dir(&dir).map_err(|e| e.to_string())?;
+
+    let mut templates: Vec<TemplateInfo> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == TEMPLATE_EXTENSION)
+        })
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            let errors = match fs::read_to_string(&path) {
+                Ok(source) => Template::parse(&source).err().unwrap_or_default(),
+                Err(e) => vec![TemplateError::new(0, None, e.to_string())],
+            };
+            Some(TemplateInfo { name, errors })
+        })
+        .collect();
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+/// Render a template with `data` and queue it on the configured PRINTER; returns the print job ID
+#[tauri::command]
+pub fn print_template(
+    name: String,
+    data: Value,
+    spooler: State<'_, Arc<PrintSpooler>>,
+    logger: State<'_, Arc<Logger>>,
+    config: State<'_, Arc<ConfigStore>>,
+) -> Result<String, String> {
+    let logger = Arc::clone(&logger);
+    let template = load_template(&name, &logger)?;
+    let commands = template
+        .render(&data)
+        .map_err(|e| format!("{}.{} {}", name, TEMPLATE_EXTENSION, e))?;
+
+    let config = config.get();
+    let printer = find_printer(&config)?;
+
+    logger
+        .log(LogLevel::INFO, &format!("Printing template {}", name))
+        .ok();
+
+    spooler.submit(PrintOptions {
+        port_name: printer.port.clone(),
+        baud_rate: printer.baudrate,
+        profile: printer.profile.clone(),
+        commands,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse_errors(source: &str) -> Vec<String> {
+        Template::parse(source)
+            .unwrap_err()
+            .iter()
+            .map(|error| error.to_string())
+            .collect()
+    }
+
+    fn render(source: &str, data: Value) -> Result<Vec<PrintCommand>, String> {
+        Template::parse(source)
+            .unwrap()
+            .render(&data)
+            .map_err(|error| error.to_string())
+    }
+
+    // Type and value of every command, with two_column as "label|value"
+    fn summary(commands: &[PrintCommand]) -> Vec<String> {
+        commands
+            .iter()
+            .map(|command| match (&command.label, &command.rows) {
+                (Some(label), _) => format!(
+                    "{} {}|{}",
+                    command.commandType,
+                    label,
+                    command.value.as_deref().unwrap_or_default()
+                ),
+                (_, Some(rows)) => format!("{} {:?}", command.commandType, rows),
+                _ => match &command.value {
+                    Some(value) => format!("{} {}", command.commandType, value),
+                    None => command.commandType.clone(),
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fills_placeholders_from_data_and_loop_items() {
+        let source = "\
+# Seat slip
+alignment center
+text Seat {{ seat.no }} in {{room}}
+two_column Users | {{ count }}
+if vip
+  bold
+else
+  text regular
+end
+each people as person
+  row {{ person.name }} | {{ person.age }}
+end
+cut";
+        let data = json!({
+            "seat": { "no": "112" },
+            "room": "열람실",
+            "count": 2,
+            "vip": false,
+            "people": [{ "name": "A", "age": 30 }, { "name": "B", "age": null }],
+        });
+        assert_eq!(
+            summary(&render(source, data).unwrap()),
+            vec![
+                "alignment center",
+                "text Seat 112 in 열람실",
+                "two_column Users|2",
+                "text regular",
+                r#"table [["A", "30"], ["B", ""]]"#,
+                "cut",
+            ]
+        );
+    }
+
+    #[test]
+    fn if_not_and_missing_lists() {
+        let source = "if not items\ntext none\nend\neach items as item\ntext {{ item }}\nend";
+        assert_eq!(
+            summary(&render(source, json!({})).unwrap()),
+            vec!["text none"]
+        );
+        assert_eq!(
+            summary(&render(source, json!({ "items": ["a", "b"] })).unwrap()),
+            vec!["text a", "text b"]
+        );
+    }
+
+    #[test]
+    fn reports_every_parse_error_with_its_position() {
+        let source = "\
+text {{ name
+  bogus 1
+alignment
+two_column only one
+text {{ bad path! }}
+each list
+end
+if
+else
+else
+end
+end";
+        assert_eq!(
+            parse_errors(source),
+            vec![
+                "line 1, column 6: unclosed placeholder, expected '}}'",
+                "line 2, column 3: unknown command 'bogus'",
+                "line 3, column 1: 'alignment' needs a value",
+                "line 4, column 12: expected 'two_column <label> | <value>'",
+                "line 5, column 6: invalid placeholder '{{ bad path! }}'",
+                "line 6, column 6: expected 'each <list> as <name>'",
+                "line 8, column 3: invalid data path ''",
+                "line 10: unexpected 'else' in 'if' block",
+                "line 11: 'end' without 'if' or 'each'",
+                "line 12: 'end' without 'if' or 'each'",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_unclosed_blocks_at_their_opening_line() {
+        assert_eq!(
+            parse_errors("text a\nif x\neach x as y\ntext b"),
+            vec![
+                "line 2: 'if' is never closed with 'end'",
+                "line 3: 'each' is never closed with 'end'",
+            ]
+        );
+    }
+
+    #[test]
+    fn placeholder_columns_count_characters_not_bytes() {
+        assert_eq!(
+            parse_errors("row 좌석 | {{ }}"),
+            vec!["line 1, column 10: invalid placeholder '{{ }}'"]
+        );
+    }
+
+    #[test]
+    fn render_errors_name_the_missing_or_unprintable_data() {
+        let source = "text Seat {{ seat }}";
+        assert_eq!(
+            render(source, json!({})).unwrap_err(),
+            "line 1, column 6: no data for 'seat'"
+        );
+        assert_eq!(
+            render(source, json!({ "seat": { "no": 1 } })).unwrap_err(),
+            "line 1, column 6: 'seat' is a list or object and can't be printed"
+        );
+        assert_eq!(
+            render("each seat as s\nend", json!({ "seat": "1" })).unwrap_err(),
+            "line 1: 'seat' is not a list"
+        );
+    }
+}
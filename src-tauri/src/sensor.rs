@@ -0,0 +1,392 @@
+use crate::logger::Logger;
+use crate::store::{
+    CombineRule, HumanSensorSettings, PowerPin, SensorInput, SensorPin, SensorSource,
+};
+use serialport::{DataBits, Parity, SerialPort, StopBits};
+use std::fs;
+use std::io::{ErrorKind, Read};
+use std::time::{Duration, Instant};
+
+const PIN_READ_TIMEOUT: Duration = Duration::from_millis(100);
+const FRAME_READ_TIMEOUT: Duration = Duration::from_millis(20);
+const FRAME_READ_CHUNK: usize = 256;
+
+/// One reading of a human sensor, whatever it is connected through
+pub struct SensorReading {
+    pub levels: Vec<(String, bool)>, // Raw input levels by name, for the human-sensor-state event
+    pub detected: bool,
+}
+
+/// Source of raw presence samples; debouncing happens in the monitoring thread
+pub trait SensorReader: Send {
+    fn read(&mut self) -> Result<SensorReading, String>;
+}
+
+struct ModemPinReader {
+    port: Box<dyn SerialPort>,
+    inputs: Vec<SensorInput>,
+    combine: CombineRule,
+}
+
+impl SensorReader for ModemPinReader {
+    fn read(&mut self) -> Result<SensorReading, String> {
+        let mut levels = Vec::with_capacity(self.inputs.len());
+        for input in &self.inputs {
+            let level = match input.pin {
+                SensorPin::Cts => self.port.read_clear_to_send(),
+                SensorPin::Dsr => self.port.read_data_set_ready(),
+                SensorPin::Dcd => self.port.read_carrier_detect(),
+                SensorPin::Ri => self.port.read_ring_indicator(),
+            }
+            .map_err(|e| format!("Failed to read {:?}: {}", input.pin, e))?;
+            // Apply the configured polarity
+            levels.push((
+                format!("{:?}", input.pin).to_lowercase(),
+                level != input.inverted,
+            ));
+        }
+
+        let detected = match self.combine {
+            CombineRule::Any => levels.iter().any(|(_, level)| *level),
+            CombineRule::All => !levels.is_empty() && levels.iter().all(|(_, level)| *level),
+        };
+        Ok(SensorReading { levels, detected })
+    }
+}
+
+/// Byte pattern where None matches any byte
+pub type FramePattern = Vec<Option<u8>>;
+
+pub fn parse_pattern(pattern: &str) -> Result<FramePattern, String> {
+    let bytes: FramePattern = pattern
+        .split_whitespace()
+        .map(|byte| match byte {
+            "??" => Ok(None),
+            _ => u8::from_str_radix(byte, 16)
+                .map(Some)
+                .map_err(|_| format!("Invalid byte '{}' in sensor frame pattern", byte)),
+        })
+        .collect::<Result<_, _>>()?;
+    if bytes.is_empty() {
+        return Err("Sensor frame pattern is empty".to_string());
+    }
+    Ok(bytes)
+}
+
+fn matches_at(buffer: &[u8], start: usize, pattern: &FramePattern) -> bool {
+    buffer.len() - start >= pattern.len()
+        && pattern
+            .iter()
+            .zip(&buffer[start..])
+            .all(|(expected, byte)| expected.is_none_or(|expected| expected == *byte))
+}
+
+struct SerialFrameReader {
+    port: Box<dyn SerialPort>,
+    present: FramePattern,
+    absent: Option<FramePattern>,
+    frame_timeout: Option<Duration>,
+    buffer: Vec<u8>,
+    last_present: Option<Instant>,
+}
+
+impl SerialFrameReader {
+    fn longest_pattern(&self) -> usize {
+        self.present
+            .len()
+            .max(self.absent.as_ref().map_or(0, |absent| absent.len()))
+    }
+}
+
+impl SensorReader for SerialFrameReader {
+    fn read(&mut self) -> Result<SensorReading, String> {
+        let mut chunk = [0u8; FRAME_READ_CHUNK];
+        match self.port.read(&mut chunk) {
+            Ok(count) => self.buffer.extend_from_slice(&chunk[..count]),
+            Err(e) if e.kind() == ErrorKind::TimedOut => {}
+            Err(e) => return Err(format!("Failed to read sensor frames: {}", e)),
+        }
+
+        // Later frames win, so scan the whole buffer in order
+        let mut position = 0;
+        let mut consumed = 0;
+        while position < self.buffer.len() {
+            if let Some(absent) = self
+                .absent
+                .as_ref()
+                .filter(|absent| matches_at(&self.buffer, position, absent))
+            {
+                self.last_present = None;
+                position += absent.len();
+                consumed = position;
+            } else if matches_at(&self.buffer, position, &self.present) {
+                self.last_present = Some(Instant::now());
+                position += self.present.len();
+                consumed = position;
+            } else {
+                position += 1;
+            }
+        }
+        // Keep a tail that could still be the start of a frame
+        let keep_from = consumed.max(self.buffer.len().saturating_sub(self.longest_pattern() - 1));
+        self.buffer.drain(..keep_from);
+
+        let detected = match (self.last_present, self.frame_timeout) {
+            (Some(at), Some(timeout)) => at.elapsed() < timeout,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        Ok(SensorReading {
+            levels: vec![("frame".to_string(), detected)],
+            detected,
+        })
+    }
+}
+
+struct SysfsGpioReader {
+    path: String,
+    active_low: bool,
+}
+
+impl SensorReader for SysfsGpioReader {
+    fn read(&mut self) -> Result<SensorReading, String> {
+        let value = fs::read_to_string(&self.path)
+            .map_err(|e| format!("Failed to read GPIO {}: {}", self.path, e))?;
+        let level = (value.trim() == "1") != self.active_low;
+        Ok(SensorReading {
+            levels: vec![("gpio".to_string(), level)],
+            detected: level,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[allow(dead_code)] // Most ABI struct fields only exist for the layout
+mod gpio_chip {
+    use super::{SensorReader, SensorReading};
+    use std::fs::OpenOptions;
+    use std::mem::size_of;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    // Kernel GPIO character device ABI v2 (linux/gpio.h)
+    const GPIO_V2_LINES_MAX: usize = 64;
+    const GPIO_MAX_NAME_SIZE: usize = 32;
+    const GPIO_V2_LINE_NUM_ATTRS_MAX: usize = 10;
+    const GPIO_V2_LINE_FLAG_ACTIVE_LOW: u64 = 1 << 1;
+    const GPIO_V2_LINE_FLAG_INPUT: u64 = 1 << 2;
+    const CONSUMER: &[u8] = b"snu-kiosk";
+
+    #[repr(C)]
+    struct LineAttribute {
+        id: u32,
+        padding: u32,
+        value: u64,
+    }
+
+    #[repr(C)]
+    struct LineConfigAttribute {
+        attr: LineAttribute,
+        mask: u64,
+    }
+
+    #[repr(C)]
+    struct LineConfig {
+        flags: u64,
+        num_attrs: u32,
+        padding: [u32; 5],
+        attrs: [LineConfigAttribute; GPIO_V2_LINE_NUM_ATTRS_MAX],
+    }
+
+    #[repr(C)]
+    struct LineRequest {
+        offsets: [u32; GPIO_V2_LINES_MAX],
+        consumer: [u8; GPIO_MAX_NAME_SIZE],
+        config: LineConfig,
+        num_lines: u32,
+        event_buffer_size: u32,
+        padding: [u32; 5],
+        fd: i32,
+    }
+
+    #[repr(C)]
+    struct LineValues {
+        bits: u64,
+        mask: u64,
+    }
+
+    const fn iowr(number: u64, size: usize) -> u64 {
+        (3 << 30) | ((size as u64) << 16) | (0xB4 << 8) | number
+    }
+
+    const GPIO_V2_GET_LINE_IOCTL: u64 = iowr(0x07, size_of::<LineRequest>());
+    const GPIO_V2_LINE_GET_VALUES_IOCTL: u64 = iowr(0x0E, size_of::<LineValues>());
+
+    pub struct GpioChipReader {
+        line: OwnedFd,
+    }
+
+    impl GpioChipReader {
+        pub fn open(chip: &str, line: u32, active_low: bool) -> Result<Self, String> {
+            let device = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(chip)
+                .map_err(|e| format!("Failed to open GPIO chip {}: {}", chip, e))?;
+
+            // SAFETY: the request is plain integers, for which all zeroes is valid
+            let mut request: LineRequest = unsafe { std::mem::zeroed() };
+            request.offsets[0] = line;
+            request.consumer[..CONSUMER.len()].copy_from_slice(CONSUMER);
+            request.config.flags = GPIO_V2_LINE_FLAG_INPUT;
+            if active_low {
+                request.config.flags |= GPIO_V2_LINE_FLAG_ACTIVE_LOW;
+            }
+            request.num_lines = 1;
+
+            // SAFETY: the request matches the kernel's struct gpio_v2_line_request
+            let result = unsafe {
+                libc::ioctl(
+                    device.as_raw_fd(),
+                    GPIO_V2_GET_LINE_IOCTL as _,
+                    &mut request as *mut LineRequest,
+                )
+            };
+            if result < 0 {
+                return Err(format!(
+                    "Failed to request line {} of {}: {}",
+                    line,
+                    chip,
+                    std::io::Error::last_os_error()
+                ));
+            }
+
+            // SAFETY: the kernel returned a new file descriptor we now own
+            let line = unsafe { OwnedFd::from_raw_fd(request.fd) };
+            Ok(GpioChipReader { line })
+        }
+    }
+
+    impl SensorReader for GpioChipReader {
+        fn read(&mut self) -> Result<SensorReading, String> {
+            let mut values = LineValues { bits: 0, mask: 1 };
+            // SAFETY: values matches the kernel's struct gpio_v2_line_values
+            let result = unsafe {
+                libc::ioctl(
+                    self.line.as_raw_fd(),
+                    GPIO_V2_LINE_GET_VALUES_IOCTL as _,
+                    &mut values as *mut LineValues,
+                )
+            };
+            if result < 0 {
+                return Err(format!(
+                    "Failed to read GPIO line: {}",
+                    std::io::Error::last_os_error()
+                ));
+            }
+
+            // The kernel has already applied the active-low flag
+            let level = values.bits & 1 != 0;
+            Ok(SensorReading {
+                levels: vec![("gpio".to_string(), level)],
+                detected: level,
+            })
+        }
+    }
+}
+
+fn open_serial(
+    port_name: &str,
+    baud_rate: u32,
+    timeout: Duration,
+) -> Result<Box<dyn SerialPort>, String> {
+    serialport::new(port_name, baud_rate)
+        .data_bits(DataBits::Eight)
+        .stop_bits(StopBits::One)
+        .parity(Parity::None)
+        .timeout(timeout)
+        .open()
+        .map_err(|e| format!("Failed to open human sensor port {}: {}", port_name, e))
+}
+
+// Power the sensor before reading it
+fn raise_power_pins(port: &mut Box<dyn SerialPort>, pins: &[PowerPin], logger: &Logger) {
+    for pin in pins {
+        let result = match pin {
+            PowerPin::Dtr => port.write_data_terminal_ready(true),
+            PowerPin::Rts => port.write_request_to_send(true),
+        };
+        if let Err(e) = result {
+            logger
+                .log_error(
+                    &format!("Failed to raise {:?} on human sensor port: {}", pin, e),
+                    file!(),
+                    "raise_power_pins",
+                    line!(),
+                )
+                .ok();
+        }
+    }
+}
+
+/// Open the human sensor configured in `settings`.
+/// Serial sources use the given port; GPIO sources ignore it.
+pub fn open_sensor_reader(
+    settings: &HumanSensorSettings,
+    port_name: &str,
+    baud_rate: u32,
+    logger: &Logger,
+) -> Result<Box<dyn SensorReader>, String> {
+    match &settings.source {
+        SensorSource::ModemPins => {
+            let mut port = open_serial(port_name, baud_rate, PIN_READ_TIMEOUT)?;
+            raise_power_pins(&mut port, &settings.power_pins, logger);
+            Ok(Box::new(ModemPinReader {
+                port,
+                inputs: settings.inputs.clone(),
+                combine: settings.combine,
+            }))
+        }
+        SensorSource::SerialFrame {
+            present_pattern,
+            absent_pattern,
+            frame_timeout_ms,
+        } => {
+            let present = parse_pattern(present_pattern)?;
+            let absent = absent_pattern.as_deref().map(parse_pattern).transpose()?;
+            let mut port = open_serial(port_name, baud_rate, FRAME_READ_TIMEOUT)?;
+            raise_power_pins(&mut port, &settings.power_pins, logger);
+            Ok(Box::new(SerialFrameReader {
+                port,
+                present,
+                absent,
+                frame_timeout: Some(Duration::from_millis(*frame_timeout_ms))
+                    .filter(|timeout| !timeout.is_zero()),
+                buffer: Vec::new(),
+                last_present: None,
+            }))
+        }
+        SensorSource::SysfsGpio { path, active_low } => {
+            let mut reader = SysfsGpioReader {
+                path: path.clone(),
+                active_low: *active_low,
+            };
+            // Fail now rather than on every poll if the line isn't exported
+            reader.read()?;
+            Ok(Box::new(reader))
+        }
+        #[cfg(target_os = "linux")]
+        SensorSource::GpioChip {
+            chip,
+            line,
+            active_low,
+        } => Ok(Box::new(gpio_chip::GpioChipReader::open(
+            chip,
+            *line,
+            *active_low,
+        )?)),
+        #[cfg(not(target_os = "linux"))]
+        SensorSource::GpioChip { .. } => {
+            Err("GPIO character devices are only supported on Linux".to_string())
+        }
+    }
+}
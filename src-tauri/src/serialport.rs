@@ -1,30 +1,53 @@
+use crate::config_store::ConfigStore;
+use crate::idle::IdleController;
 use crate::logger::{LogLevel, Logger};
-use encoding_rs::EUC_KR;
+use crate::occupancy::OccupancyLog;
+use crate::presence::{scheduled_mode, PresenceDetector, PresenceEvent, SensorMode};
+use crate::sensor::open_sensor_reader;
+use crate::store::{Config, PresenceDebounce, SerialData};
+use chrono::Local;
 use lazy_static::lazy_static;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use serialport::{available_ports, DataBits, Error, Parity, SerialPort, StopBits};
+use std::collections::HashMap;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc, Mutex,
 };
 use std::thread::JoinHandle;
 use std::thread;
-use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const READER_STOP_TIMEOUT: Duration = Duration::from_secs(2);
+const HUMAN_SENSOR_NAME: &str = "HUMAN_SENSOR";
+
+/// Reported by `get_human_sensor_status` and the `human-sensor-status` event
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HumanSensorStatus {
+    monitoring: bool,
+    detection_enabled: bool, // The humanSensorDetection flag
+    active: bool,            // Detection enabled and not switched off by the schedule
+    schedule_window: Option<String>,
+    debounce: Option<PresenceDebounce>,
+}
 
 // Human Sensor State Structure
 pub struct HumanSensorState {
-    port: Option<Box<dyn SerialPort>>,
     continue_monitoring: Arc<AtomicBool>,
     thread_handle: Option<JoinHandle<()>>,
+    status: Arc<Mutex<HumanSensorStatus>>,
+    config_entry: Option<SerialData>, // HUMAN_SENSOR entry the sensor was opened with
 }
 
 impl HumanSensorState {
     pub fn new() -> Self {
         HumanSensorState {
-            port: None,
             continue_monitoring: Arc::new(AtomicBool::new(true)),
             thread_handle: None,
+            status: Arc::new(Mutex::new(HumanSensorStatus::default())),
+            config_entry: None,
         }
     }
 
@@ -33,15 +56,20 @@ impl HumanSensorState {
         
         if let Some(handle) = self.thread_handle.take() {
             match handle.join() {
+                // The sensor is closed when the thread drops its reader
                 Ok(()) => println!("Human sensor monitoring thread stopped successfully."),
                 Err(_) => println!("Error waiting for human sensor thread to stop."),
             }
         }
-        
-        if let Some(port) = self.port.take() {
-            drop(port);
-            println!("Human sensor port has been closed.");
-        }
+        *lock_status(&self.status) = HumanSensorStatus::default();
+        self.config_entry = None;
+    }
+}
+
+fn lock_status(status: &Mutex<HumanSensorStatus>) -> std::sync::MutexGuard<'_, HumanSensorStatus> {
+    match status.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
     }
 }
 
@@ -115,6 +143,27 @@ lazy_static! {
     pub static ref SERIAL_STATE: Mutex<SharedSerialState> = Mutex::new(SharedSerialState::new());
 }
 
+// A `continuous_read` reader, kept by device name so a config change can reopen it
+struct ActiveReader {
+    port_name: String,
+    baud_rate: u32,
+    continue_reading: Arc<AtomicBool>,
+    finished: Arc<AtomicBool>,
+}
+
+lazy_static! {
+    static ref ACTIVE_READERS: Mutex<HashMap<String, ActiveReader>> = Mutex::new(HashMap::new());
+    // Held for a whole reload so quick successive config changes apply in order
+    static ref RELOAD_LOCK: Mutex<()> = Mutex::new(());
+}
+
+fn lock_readers() -> std::sync::MutexGuard<'static, HashMap<String, ActiveReader>> {
+    match ACTIVE_READERS.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
 #[derive(Serialize)]
 pub struct SerialHealthInfo {
     uptime_seconds: u64,
@@ -211,8 +260,22 @@ pub fn continuous_read(
     logger: tauri::State<'_, Arc<Logger>>,
     app_handle: AppHandle,
 ) -> Result<(), String> {
-    let logger = Arc::clone(&logger);
+    open_reader(
+        port_name,
+        baud_rate,
+        device_name,
+        Arc::clone(&logger),
+        app_handle,
+    )
+}
 
+fn open_reader(
+    port_name: String,
+    baud_rate: u32,
+    device_name: String,
+    logger: Arc<Logger>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
     logger
         .log(
             LogLevel::INFO,
@@ -253,6 +316,17 @@ pub fn continuous_read(
     let error_count = state_guard.error_count.clone();
     let mut port = state_guard.port.take().unwrap();
 
+    let finished = Arc::new(AtomicBool::new(false));
+    lock_readers().insert(
+        device_name.clone(),
+        ActiveReader {
+            port_name: port_name.clone(),
+            baud_rate,
+            continue_reading: continue_reading.clone(),
+            finished: finished.clone(),
+        },
+    );
+
     let handle = thread::spawn(move || {
         let mut collecting = false;
         let mut data_buffer = Vec::with_capacity(1024); // Pre-allocate with reasonable capacity
@@ -334,6 +408,7 @@ pub fn continuous_read(
         }
 
         println!("Stopping continuous reading thread.");
+        finished.store(true, Ordering::SeqCst);
     });
     
     // Store the thread handle for proper cleanup
@@ -342,237 +417,71 @@ pub fn continuous_read(
     Ok(())
 }
 
-#[derive(Debug, Deserialize)]
-pub struct PrintOptions {
-    port_name: String,
-    baud_rate: u32,
-    commands: Vec<PrintCommand>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct PrintCommand {
-    #[serde(rename = "type")]
-    commandType: String,
-    value: Option<String>, // Additional data for certain commands (e.g., alignment)
-}
-
-#[tauri::command]
-pub fn print_with_options(
-    print_options: PrintOptions,
-    logger: tauri::State<'_, Arc<Logger>>,
-) -> Result<(), String> {
-    let logger = Arc::clone(&logger);
-    logger.log(LogLevel::INFO, "Starting print job").ok();
-
-    let mut port = serialport::new(&print_options.port_name, print_options.baud_rate)
-        .data_bits(DataBits::Eight)
-        .stop_bits(StopBits::One)
-        .parity(Parity::None)
-        .timeout(Duration::from_secs(5))
-        .open()
-        .map_err(|e| {
+fn emit_presence_event(app_handle: &AppHandle, logger: &Arc<Logger>, event: PresenceEvent) {
+    if let Some(idle) = app_handle.try_state::<Arc<IdleController>>() {
+        idle.on_presence(matches!(event, PresenceEvent::Arrived(_)));
+    }
+    let result = match event {
+        PresenceEvent::Arrived(arrived) => {
+            logger.log(LogLevel::INFO, "Person arrived at the kiosk").ok();
+            app_handle.emit("person-arrived", arrived)
+        }
+        PresenceEvent::Left(left) => {
+            if let Some(occupancy) = app_handle.try_state::<Arc<OccupancyLog>>() {
+                occupancy.record_session(&left);
+            }
             logger
-                .log_error(
-                    &format!("Failed to open port: {}", e),
-                    file!(),
-                    "print_with_options",
-                    line!(),
+                .log(
+                    LogLevel::INFO,
+                    &format!("Person left the kiosk after {} s", left.session_ms / 1000),
                 )
                 .ok();
-            e.to_string()
-        })?;
-
-    let assumed_dpi = 180.0; // Adjust this value as needed
-    let mm_width = 80.0;
-    let dots = (mm_width * assumed_dpi / 25.4) as u16;
-    let nL = (dots % 256) as u8;
-    let nH = (dots / 256) as u8;
-    let print_width_command = format!("\x1D\x57{}{}", nL as char, nH as char);
-    write_to_serial_port(&mut *port, print_width_command.as_bytes()).map_err(|e| e.to_string())?;
-
-    // Example: process the commands
-    for command in print_options.commands {
-        match command.commandType.as_str() {
-            "bold" => {
-                let bold_command = "\x1BE\x01"; // Command to enable bold text
-                write_to_serial_port(&mut *port, bold_command.as_bytes())
-                    .map_err(|e| e.to_string())?;
-            }
-            "unbold" => {
-                let unbold_command = "\x1BE\x00"; // Command to disable bold text
-                write_to_serial_port(&mut *port, unbold_command.as_bytes())
-                    .map_err(|e| e.to_string())?;
-            }
-            "alignment" => {
-                if let Some(value) = command.value {
-                    let alignment_command = match value.as_str() {
-                        "left" => "\x1Ba\x00",
-                        "center" => "\x1Ba\x01",
-                        "right" => "\x1Ba\x02",
-                        _ => return Err(format!("Invalid alignment value: {}", value)),
-                    };
-                    write_to_serial_port(&mut *port, alignment_command.as_bytes())
-                        .map_err(|e| e.to_string())?;
-                }
-            }
-            "text" => {
-                if let Some(value) = command.value {
-                    write_to_serial_port(&mut *port, value.as_bytes())
-                        .map_err(|e| e.to_string())?;
-                } else {
-                    return Err("Missing text value for text command".to_string());
-                }
-            }
-            "normal_text" => {
-                let normal_text_command = "\x1D\x21\x00"; // Command for normal text size
-                write_to_serial_port(&mut *port, normal_text_command.as_bytes())
-                    .map_err(|e| e.to_string())?;
-            }
-            "small_text" => {
-                let small_text_command = "\x1D\x21\x01"; // Command for small text size
-                write_to_serial_port(&mut *port, small_text_command.as_bytes())
-                    .map_err(|e| e.to_string())?;
-            }
-            "medium_text" => {
-                let large_text_command = "\x1D!\x11"; // Command for large text size
-                write_to_serial_port(&mut *port, large_text_command.as_bytes())
-                    .map_err(|e| e.to_string())?;
-            }
-            "large_text" => {
-                let large_text_command = "\x1D\x21\x11"; // Command for large text size
-                write_to_serial_port(&mut *port, large_text_command.as_bytes())
-                    .map_err(|e| e.to_string())?;
-            }
-            "korean_text" => {
-                if let Some(value) = command.value {
-                    // Encode Korean text using EUC-KR
-                    let (encoded, _, had_errors) = EUC_KR.encode(&value);
-                    if had_errors {
-                        return Err("Encoding failed for Korean text".to_string());
-                    }
-
-                    // Command to select the code page for Korean characters, if necessary
-                    let select_korean_command = "\x1B\x74\x0B"; // ESC t n - Check this command for your printer model
-                    write_to_serial_port(&mut *port, select_korean_command.as_bytes())
-                        .map_err(|e| e.to_string())?;
-
-                    // Send the encoded text directly
-                    write_to_serial_port(&mut *port, encoded.as_ref())
-                        .map_err(|e| e.to_string())?;
-
-                    // Optionally reset to default character code table if needed
-                    let reset_code_table = "\x1B\x74\x00"; // Reset to default page
-                    write_to_serial_port(&mut *port, reset_code_table.as_bytes())
-                        .map_err(|e| e.to_string())?;
-                } else {
-                    return Err("Missing text value for Korean text command".to_string());
-                }
-            }
-            "blank_line" => {
-                // Command to print a blank line
-                let blank_line_command = "\n"; // Assuming a newline is sufficient for a blank line
-                write_to_serial_port(&mut *port, blank_line_command.as_bytes())
-                    .map_err(|e| e.to_string())?;
-            }
-            "full_cut" => {
-                // Command for full cut (example command, adjust as needed)
-                let full_cut_command = "\x1DV\x00"; // Example command for full cut
-                write_to_serial_port(&mut *port, full_cut_command.as_bytes())
-                    .map_err(|e| e.to_string())?;
-            }
-            "clear_all" => {
-                // Command to clear all settings (example command, adjust as needed)
-                let clear_all_command = "\x1B\x40"; // Example command to reset printer settings
-                write_to_serial_port(&mut *port, clear_all_command.as_bytes())
-                    .map_err(|e| e.to_string())?;
-            }
-            "qr_code" => {
-                if let Some(value) = command.value {
-                    if value.len() > 230 {
-                        // Adjust based on your QR code data capacity needs
-                        return Err("Data too long for QR code capacity".to_string());
-                    }
-
-                    let mut command_bytes = Vec::new();
-
-                    // Center alignment for CODE type
-                    command_bytes.extend_from_slice(b"\x1Ba\x01"); // ESC a 1 (Center Alignment)
-
-                    // Settings (Using example values, replace with actual settings if needed)
-                    let _height: u8 = 5; // Example height
-                    let _width: u8 = 5; // Example width
-                    let margin: u8 = 0; // Example margin (multiplied by 8 for mm conversion)
-
-                    // Margin setting for the printer
-                    let margin_mm = margin * 8;
-                    command_bytes.push(0x1D);
-                    command_bytes.push(b'L');
-                    command_bytes.push(margin_mm);
-                    command_bytes.push(0x00);
-
-                    // Check for 2D barcode (QR Code) and append the corresponding command
-                    if value.len() > 0 {
-                        // Assuming 'VIEW' check translates to this
-                        command_bytes.push(0x1A);
-                        command_bytes.push(b'B');
-                        command_bytes.push(2); // Constant indicating QR Code in your Node.js example
-                        command_bytes.push(value.len() as u8); // Length of the data to be encoded
-                        command_bytes.push(5); // Placeholder, adjust if necessary
-                        command_bytes.extend_from_slice(value.as_bytes());
-                        command_bytes.push(0x00); // Null terminator for string
-                        command_bytes.push(b'\n'); // New line if needed (check your printer's requirements)
-                    }
-
-                    // Write the complete command to the serial port
-                    write_to_serial_port(&mut *port, &command_bytes).map_err(|e| e.to_string())?;
-                } else {
-                    return Err("Missing value for QR code command".to_string());
-                }
-            }
-            // Add other commands like "small_text", "barcode" here as in the original code.
-            _ => {
-                return Err(format!("Unsupported command type: {}", command.commandType));
-            }
+            app_handle.emit("person-left", left)
         }
+    };
+    if let Err(e) = result {
+        println!("Failed to emit presence event: {}", e);
     }
-
-    Ok(())
 }
 
-fn write_to_serial_port(port: &mut dyn SerialPort, data: &[u8]) -> Result<usize, std::io::Error> {
-    match port.write(&data) {
-        Ok(nbytes) => {
-            if nbytes < data.len() {
-                eprintln!(
-                    "Warning: Not all data was written to the port. {} bytes written out of {}",
-                    nbytes,
-                    data.len()
-                );
-                // Try to flush the port to ensure data is sent
-                if let Err(e) = port.flush() {
-                    eprintln!("Failed to flush serial port: {}", e);
-                }
-            } else {
-                println!("Data written successfully");
-                // Flush the port after successful write
-                if let Err(e) = port.flush() {
-                    eprintln!("Failed to flush serial port: {}", e);
-                }
-            }
-            Ok(nbytes)
-        }
-        Err(e) => {
-            eprintln!("Failed to write to serial port: {}", e);
-            Err(e)
+// Switch the human sensor on, off or to other debounce settings as the schedule says
+fn apply_sensor_mode(
+    app_handle: &AppHandle,
+    logger: &Arc<Logger>,
+    status: &Mutex<HumanSensorStatus>,
+    detector: &mut PresenceDetector,
+    mode: &SensorMode,
+) {
+    if !mode.active {
+        if let Some(event) = detector.end_session() {
+            emit_presence_event(app_handle, logger, event);
         }
     }
-}
+    detector.set_debounce(mode.debounce);
+    if let Some(idle) = app_handle.try_state::<Arc<IdleController>>() {
+        idle.on_sensor_active(mode.active);
+    }
 
-// Human Sensor Pin State Structure
-#[derive(Debug, Clone, Serialize)]
-pub struct PinState {
-    cts: bool,
-    dsr: bool,
+    let snapshot = {
+        let mut status = lock_status(status);
+        status.active = mode.active;
+        status.schedule_window = mode.window.clone();
+        status.debounce = Some(mode.debounce);
+        status.clone()
+    };
+    logger
+        .log(
+            LogLevel::INFO,
+            &format!(
+                "Human sensor {} ({})",
+                if mode.active { "active" } else { "off" },
+                mode.window.as_deref().unwrap_or("no schedule window")
+            ),
+        )
+        .ok();
+    if let Err(e) = app_handle.emit("human-sensor-status", snapshot) {
+        println!("Failed to emit human-sensor-status event: {}", e);
+    }
 }
 
 #[tauri::command]
@@ -582,8 +491,15 @@ pub fn start_human_sensor_monitoring(
     logger: tauri::State<'_, Arc<Logger>>,
     app_handle: AppHandle,
 ) -> Result<(), String> {
-    let logger = Arc::clone(&logger);
+    start_sensor(&port_name, baud_rate, Arc::clone(&logger), app_handle)
+}
 
+fn start_sensor(
+    port_name: &str,
+    baud_rate: u32,
+    logger: Arc<Logger>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
     logger
         .log(
             LogLevel::INFO,
@@ -605,7 +521,7 @@ pub fn start_human_sensor_monitoring(
     };
 
     // If already monitoring, stop first
-    if state_guard.port.is_some() {
+    if state_guard.thread_handle.is_some() {
         logger
             .log(LogLevel::INFO, "Stopping existing human sensor monitoring")
             .ok();
@@ -615,83 +531,105 @@ pub fn start_human_sensor_monitoring(
     // Reset the continue_monitoring flag to true for the new session
     state_guard.continue_monitoring.store(true, Ordering::SeqCst);
 
-    // Open the serial port
-    let port = serialport::new(&port_name, baud_rate)
-        .data_bits(DataBits::Eight)
-        .stop_bits(StopBits::One)
-        .parity(Parity::None)
-        .timeout(Duration::from_millis(100))
-        .open()
-        .map_err(|e| {
-            logger
-                .log_error(
-                    &format!("Failed to open human sensor port: {}", e),
-                    file!(),
-                    "start_human_sensor_monitoring",
-                    line!(),
-                )
-                .ok();
-            e.to_string()
-        })?;
-
-    println!("Human sensor port {} opened successfully.", port_name);
+    let config = app_handle
+        .try_state::<Arc<ConfigStore>>()
+        .map(|config| config.get());
+    let detection_enabled = config
+        .as_ref()
+        .is_none_or(|config| config.humanSensorDetection);
+    let config_entry = config.and_then(|config| {
+        config
+            .serialdata
+            .into_iter()
+            .find(|entry| entry.name == HUMAN_SENSOR_NAME)
+    });
+    let settings = config_entry
+        .as_ref()
+        .and_then(|entry| entry.sensor.clone())
+        .unwrap_or_default();
 
-    let continue_monitoring = state_guard.continue_monitoring.clone();
-    
-    // Clone the port for the thread
-    let mut port_for_thread = port.try_clone().map_err(|e| {
+    let mut reader = open_sensor_reader(&settings, port_name, baud_rate, &logger).map_err(|e| {
         logger
             .log_error(
-                &format!("Failed to clone port: {}", e),
+                &format!("Failed to open human sensor: {}", e),
                 file!(),
                 "start_human_sensor_monitoring",
                 line!(),
             )
             .ok();
-        e.to_string()
+        e
     })?;
 
+    println!("Human sensor {:?} opened successfully.", settings.source);
+
+    let thread_logger = Arc::clone(&logger);
+
+    let continue_monitoring = state_guard.continue_monitoring.clone();
+    let status = Arc::clone(&state_guard.status);
+    *lock_status(&status) = HumanSensorStatus {
+        monitoring: true,
+        detection_enabled,
+        ..HumanSensorStatus::default()
+    };
+
     let handle = thread::spawn(move || {
-        let mut last_state = PinState {
-            cts: false,
-            dsr: false,
-        };
+        let mut last_levels: Vec<(String, bool)> = Vec::new();
+        let mut detector = PresenceDetector::new(settings.debounce);
+        let mut mode: Option<SensorMode> = None;
+        let mut schedule_checked_at: Option<Instant> = None;
 
         println!("Human sensor monitoring thread started.");
 
         while continue_monitoring.load(Ordering::SeqCst) {
-            // Read CTS and DSR pin states
-            match (port_for_thread.read_clear_to_send(), port_for_thread.read_data_set_ready()) {
-                (Ok(cts), Ok(dsr)) => {
+            if schedule_checked_at.is_none_or(|at| at.elapsed() >= SCHEDULE_CHECK_INTERVAL) {
+                schedule_checked_at = Some(Instant::now());
+                let next = scheduled_mode(&settings, detection_enabled, Local::now().naive_local());
+                if mode.as_ref() != Some(&next) {
+                    apply_sensor_mode(&app_handle, &thread_logger, &status, &mut detector, &next);
+                    if !next.active {
+                        last_levels.clear();
+                    }
+                    mode = Some(next);
+                }
+            }
+            let active = mode.as_ref().is_some_and(|mode| mode.active);
+
+            match reader.read() {
+                // Keep draining the sensor while it is off so stale input isn't picked up later
+                _ if !active => {}
+                Ok(reading) => {
                     // Only emit event if state has changed
-                    if cts != last_state.cts || dsr != last_state.dsr {
-                        let new_state = PinState { cts, dsr };
-                        
-                        println!(
-                            "Human sensor state changed - CTS: {}, DSR: {}",
-                            cts, dsr
-                        );
+                    if reading.levels != last_levels {
+                        let mut payload = serde_json::Map::new();
+                        for (name, level) in &reading.levels {
+                            payload.insert(name.clone(), serde_json::Value::Bool(*level));
+                        }
+                        println!("Human sensor state changed - {:?}", payload);
 
-                        if let Err(e) = app_handle.emit(
-                            "human-sensor-state",
-                            serde_json::json!({
-                                "cts": cts,
-                                "dsr": dsr,
-                                "detected": cts || dsr,
-                                "timestamp": std::time::SystemTime::now()
-                                    .duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap_or_default()
-                                    .as_millis()
-                            }),
-                        ) {
+                        payload.insert(
+                            "detected".to_string(),
+                            serde_json::Value::Bool(reading.detected),
+                        );
+                        payload.insert(
+                            "timestamp".to_string(),
+                            serde_json::json!(std::time::SystemTime::now()
+                                .duration_since(std::time::UNIX_EPOCH)
+                                .unwrap_or_default()
+                                .as_millis() as u64),
+                        );
+                        if let Err(e) = app_handle.emit("human-sensor-state", payload) {
                             println!("Failed to emit human-sensor-state event: {}", e);
                         }
 
-                        last_state = new_state;
+                        last_levels = reading.levels;
+                    }
+
+                    if let Some(event) = detector.update(reading.detected, Instant::now()) {
+                        emit_presence_event(&app_handle, &thread_logger, event);
                     }
                 }
-                (Err(e), _) | (_, Err(e)) => {
-                    println!("Error reading pin states: {}", e);
+                Err(e) => {
+                    println!("Error reading human sensor: {}", e);
                 }
             }
 
@@ -699,11 +637,14 @@ pub fn start_human_sensor_monitoring(
             thread::sleep(Duration::from_millis(50));
         }
 
+        if let Some(event) = detector.end_session() {
+            emit_presence_event(&app_handle, &thread_logger, event);
+        }
         println!("Human sensor monitoring thread stopped.");
     });
 
-    state_guard.port = Some(port);
     state_guard.thread_handle = Some(handle);
+    state_guard.config_entry = config_entry;
 
     logger
         .log(LogLevel::INFO, "Human sensor monitoring started successfully")
@@ -739,3 +680,167 @@ pub fn stop_human_sensor_monitoring(logger: tauri::State<'_, Arc<Logger>>) -> Re
     
     Ok(())
 }
+
+/// Whether the human sensor is monitored and what its schedule currently says
+#[tauri::command]
+pub fn get_human_sensor_status(
+    logger: tauri::State<'_, Arc<Logger>>,
+) -> Result<HumanSensorStatus, String> {
+    let state_guard = match HUMAN_SENSOR_STATE.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            logger
+                .log_error(
+                    "Mutex was poisoned in get_human_sensor_status, recovering",
+                    file!(),
+                    "get_human_sensor_status",
+                    line!(),
+                )
+                .ok();
+            poisoned.into_inner()
+        }
+    };
+
+    let status = lock_status(&state_guard.status).clone();
+    Ok(status)
+}
+
+/// Bring running readers in line with a changed config. Only a reader whose
+/// port or baud rate changed is reopened; the human sensor is restarted when its
+/// entry or the detection flag changed, and readers whose entry is gone are stopped.
+/// Stopping a reader can take a while, so the reload runs on its own thread.
+pub fn reload_serial_devices(app_handle: AppHandle, logger: Arc<Logger>, config: Config) {
+    thread::spawn(move || {
+        let _reload = match RELOAD_LOCK.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        reload_devices(&app_handle, &logger, &config);
+    });
+}
+
+fn reload_devices(app_handle: &AppHandle, logger: &Arc<Logger>, config: &Config) {
+    let entry = |name: &str| {
+        config
+            .serialdata
+            .iter()
+            .find(|entry| entry.name == name && !entry.port.trim().is_empty())
+            .cloned()
+    };
+
+    let changed: Vec<(String, ActiveReader, Option<SerialData>)> = {
+        let mut readers = lock_readers();
+        // Readers stopped by `stop_serial_reading` or a read error are not revived
+        readers.retain(|_, reader| {
+            reader.continue_reading.load(Ordering::SeqCst)
+                && !reader.finished.load(Ordering::SeqCst)
+        });
+        let names: Vec<String> = readers
+            .iter()
+            .filter(|(name, reader)| {
+                entry(name).is_none_or(|entry| {
+                    entry.port != reader.port_name || entry.baudrate != reader.baud_rate
+                })
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let reader = readers.remove(&name)?;
+                let entry = entry(&name);
+                Some((name, reader, entry))
+            })
+            .collect()
+    };
+
+    // Stop all changed readers first so their shutdowns overlap
+    for (_, reader, _) in &changed {
+        reader.continue_reading.store(false, Ordering::SeqCst);
+    }
+    let stopping = Instant::now();
+    while changed
+        .iter()
+        .any(|(_, reader, _)| !reader.finished.load(Ordering::SeqCst))
+        && stopping.elapsed() < READER_STOP_TIMEOUT
+    {
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    for (name, _, entry) in changed {
+        let Some(entry) = entry else {
+            logger
+                .log(
+                    LogLevel::INFO,
+                    &format!("Stopped {} reader, it is no longer configured", name),
+                )
+                .ok();
+            continue;
+        };
+        logger
+            .log(
+                LogLevel::INFO,
+                &format!(
+                    "Reopening {} reader on {} at {} baud",
+                    name, entry.port, entry.baudrate
+                ),
+            )
+            .ok();
+        if let Err(e) = open_reader(
+            entry.port,
+            entry.baudrate,
+            name,
+            Arc::clone(logger),
+            app_handle.clone(),
+        ) {
+            logger
+                .log_error(
+                    &format!("Failed to reopen serial reader: {}", e),
+                    file!(),
+                    "reload_devices",
+                    line!(),
+                )
+                .ok();
+        }
+    }
+
+    let restart = {
+        let mut state_guard = match HUMAN_SENSOR_STATE.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let monitoring = state_guard.thread_handle.is_some();
+        let new_entry = entry(HUMAN_SENSOR_NAME);
+        let detection_changed =
+            lock_status(&state_guard.status).detection_enabled != config.humanSensorDetection;
+        if !monitoring || (state_guard.config_entry == new_entry && !detection_changed) {
+            None
+        } else {
+            state_guard.stop_and_close();
+            new_entry
+        }
+    };
+    if let Some(entry) = restart {
+        logger
+            .log(
+                LogLevel::INFO,
+                &format!("Restarting human sensor on {}", entry.port),
+            )
+            .ok();
+        if let Err(e) = start_sensor(
+            &entry.port,
+            entry.baudrate,
+            Arc::clone(logger),
+            app_handle.clone(),
+        ) {
+            logger
+                .log_error(
+                    &format!("Failed to restart human sensor: {}", e),
+                    file!(),
+                    "reload_devices",
+                    line!(),
+                )
+                .ok();
+        }
+    }
+}
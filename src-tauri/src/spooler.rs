@@ -0,0 +1,460 @@
+use crate::config_store::ConfigStore;
+use crate::logger::{LogLevel, Logger};
+use crate::paper::PaperTracker;
+use crate::print_history::PrintHistory;
+use crate::printer::{run_print_job, PrintCommand, PrintOptions, WriteFailure};
+use crate::store::get_config_dir;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+const MAX_ATTEMPTS: u32 = 5; // Give up on a job after this many transient failures
+const RETRY_BASE_DELAY_MS: u64 = 1000; // Backoff grows linearly with the attempt count
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrintJobStatus {
+    Queued,
+    Printing,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+/// A print job as persisted in the spool directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrintJob {
+    pub id: String,
+    pub port_name: String,
+    pub baud_rate: u32,
+    #[serde(default)]
+    pub profile: Option<String>,
+    pub commands: Vec<PrintCommand>,
+    pub status: PrintJobStatus,
+    pub attempts: u32,
+    pub created_at: String,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub write_failure: Option<WriteFailure>, // How far the last attempt got when sending failed
+    #[serde(skip)]
+    not_before: Option<Instant>, // Earliest time the next retry may run
+}
+
+impl PrintJob {
+    fn options(&self) -> PrintOptions {
+        PrintOptions {
+            port_name: self.port_name.clone(),
+            baud_rate: self.baud_rate,
+            profile: self.profile.clone(),
+            commands: self.commands.clone(),
+        }
+    }
+}
+
+/// Payload of the `print-job-status` event
+#[derive(Debug, Clone, Serialize)]
+pub struct PrintJobStatusEvent {
+    job_id: String,
+    status: PrintJobStatus,
+    attempts: u32,
+    error: Option<String>,
+    bytes_written: usize, // Bytes of the last attempt that reached the printer
+    write_failure: Option<WriteFailure>,
+}
+
+struct SpoolQueue {
+    jobs: VecDeque<PrintJob>,
+    current: Option<PrintJob>, // The job currently being printed
+    cancel_current: bool,
+}
+
+/// Persistent print queue with a single worker thread.
+/// Every job lives in its own file in the spool directory until it is done,
+/// failed or cancelled, so queued slips survive an app restart.
+pub struct PrintSpooler {
+    queue: Mutex<SpoolQueue>,
+    wakeup: Condvar,
+    spool_dir: PathBuf,
+    config: Arc<ConfigStore>,
+    logger: Arc<Logger>,
+    history: Arc<PrintHistory>,
+    paper: Arc<PaperTracker>,
+    app_handle: AppHandle,
+}
+
+impl PrintSpooler {
+    pub fn start(
+        app_handle: AppHandle,
+        config: Arc<ConfigStore>,
+        logger: Arc<Logger>,
+        history: Arc<PrintHistory>,
+        paper: Arc<PaperTracker>,
+    ) -> Result<Arc<Self>, Box<dyn std::error::Error>> {
+        let spool_dir = get_config_dir(Arc::clone(&logger))?.join("print_jobs");
+        if !spool_dir.exists() {
+            fs::create_dir_all(&spool_dir)?;
+        }
+
+        let jobs = load_spooled_jobs(&spool_dir, &logger);
+        if !jobs.is_empty() {
+            logger
+                .log(
+                    LogLevel::INFO,
+                    &format!("Resuming {} spooled print job(s)", jobs.len()),
+                )
+                .ok();
+        }
+
+        let spooler = Arc::new(PrintSpooler {
+            queue: Mutex::new(SpoolQueue {
+                jobs: jobs.into(),
+                current: None,
+                cancel_current: false,
+            }),
+            wakeup: Condvar::new(),
+            spool_dir,
+            config,
+            logger,
+            history,
+            paper,
+            app_handle,
+        });
+
+        let worker = Arc::clone(&spooler);
+        thread::spawn(move || worker.run_worker());
+
+        Ok(spooler)
+    }
+
+    pub fn submit(&self, options: PrintOptions) -> Result<String, String> {
+        let job = PrintJob {
+            id: Uuid::new_v4().to_string(),
+            port_name: options.port_name,
+            baud_rate: options.baud_rate,
+            profile: options.profile,
+            commands: options.commands,
+            status: PrintJobStatus::Queued,
+            attempts: 0,
+            created_at: Local::now().to_rfc3339(),
+            last_error: None,
+            write_failure: None,
+            not_before: None,
+        };
+
+        self.persist(&job)?;
+        self.logger
+            .log(LogLevel::INFO, &format!("Print job {} queued", job.id))
+            .ok();
+        self.emit_status(&job);
+
+        let id = job.id.clone();
+        self.lock_queue().jobs.push_back(job);
+        self.wakeup.notify_one();
+        Ok(id)
+    }
+
+    pub fn cancel(&self, job_id: &str) -> Result<(), String> {
+        let mut queue = self.lock_queue();
+
+        if let Some(pos) = queue.jobs.iter().position(|job| job.id == job_id) {
+            let mut job = queue.jobs.remove(pos).unwrap();
+            drop(queue);
+            job.status = PrintJobStatus::Cancelled;
+            self.finish(&job);
+            return Ok(());
+        }
+
+        if queue.current.as_ref().is_some_and(|job| job.id == job_id) {
+            // Bytes already sent cannot be recalled; just make sure it is not retried
+            queue.cancel_current = true;
+            return Ok(());
+        }
+
+        Err(format!("Print job not found: {}", job_id))
+    }
+
+    /// The job being printed, if any, followed by the queued jobs in print order
+    pub fn list(&self) -> Vec<PrintJob> {
+        let queue = self.lock_queue();
+        queue
+            .current
+            .iter()
+            .chain(queue.jobs.iter())
+            .cloned()
+            .collect()
+    }
+
+    fn run_worker(&self) {
+        loop {
+            let mut job = self.next_job();
+            self.persist(&job).ok();
+            self.emit_status(&job);
+
+            let result = run_print_job(
+                &self.config.get(),
+                &job.port_name,
+                job.baud_rate,
+                job.profile.as_deref(),
+                &job.commands,
+                &self.logger,
+            );
+
+            let mut queue = self.lock_queue();
+            let cancelled = queue.cancel_current;
+            queue.current = None;
+            queue.cancel_current = false;
+
+            match result {
+                Ok(outcome) => {
+                    drop(queue);
+                    job.status = PrintJobStatus::Done;
+                    job.last_error = None;
+                    job.write_failure = None;
+                    self.paper.record(&outcome);
+                    self.history.record(&job.id, &job.options());
+                    self.finish(&job);
+                }
+                Err(e) if e.is_transient() && job.attempts < MAX_ATTEMPTS && !cancelled => {
+                    let delay = Duration::from_millis(RETRY_BASE_DELAY_MS * job.attempts as u64);
+                    self.logger
+                        .log(
+                            LogLevel::WARN,
+                            &format!(
+                                "Print job {} attempt {} failed, retrying in {:?}: {}",
+                                job.id, job.attempts, delay, e
+                            ),
+                        )
+                        .ok();
+                    job.status = PrintJobStatus::Queued;
+                    job.last_error = Some(e.to_string());
+                    job.write_failure = e.write_failure().cloned();
+                    job.not_before = Some(Instant::now() + delay);
+                    self.persist(&job).ok();
+                    self.emit_status(&job);
+                    // Keep the job at the front so slips still come out in order
+                    queue.jobs.push_front(job);
+                }
+                Err(e) => {
+                    drop(queue);
+                    job.status = if cancelled {
+                        PrintJobStatus::Cancelled
+                    } else {
+                        PrintJobStatus::Failed
+                    };
+                    job.last_error = Some(e.to_string());
+                    job.write_failure = e.write_failure().cloned();
+                    if job
+                        .write_failure
+                        .as_ref()
+                        .is_some_and(|failure| failure.bytes_written > 0)
+                    {
+                        // Part of the slip came out; keep the job so staff can reprint it
+                        self.history.record(&job.id, &job.options());
+                    }
+                    self.finish(&job);
+                }
+            }
+        }
+    }
+
+    /// Block until the job at the front of the queue is ready to run, and mark it printing
+    fn next_job(&self) -> PrintJob {
+        let mut queue = self.lock_queue();
+        loop {
+            let wait = match queue.jobs.front() {
+                None => None,
+                Some(job) => match job.not_before {
+                    Some(at) if at > Instant::now() => Some(at - Instant::now()),
+                    _ => {
+                        let mut job = queue.jobs.pop_front().unwrap();
+                        job.status = PrintJobStatus::Printing;
+                        job.attempts += 1;
+                        queue.current = Some(job.clone());
+                        queue.cancel_current = false;
+                        return job;
+                    }
+                },
+            };
+
+            queue = match wait {
+                Some(timeout) => match self.wakeup.wait_timeout(queue, timeout) {
+                    Ok((guard, _)) => guard,
+                    Err(poisoned) => poisoned.into_inner().0,
+                },
+                None => match self.wakeup.wait(queue) {
+                    Ok(guard) => guard,
+                    Err(poisoned) => poisoned.into_inner(),
+                },
+            };
+        }
+    }
+
+    /// Report the final status of a job and drop it from the spool directory
+    fn finish(&self, job: &PrintJob) {
+        match job.status {
+            PrintJobStatus::Failed => self
+                .logger
+                .log_error(
+                    &format!(
+                        "Print job {} failed after {} attempt(s): {}",
+                        job.id,
+                        job.attempts,
+                        job.last_error.as_deref().unwrap_or("unknown error")
+                    ),
+                    file!(),
+                    "finish",
+                    line!(),
+                )
+                .ok(),
+            _ => self
+                .logger
+                .log(
+                    LogLevel::INFO,
+                    &format!("Print job {} {:?}", job.id, job.status),
+                )
+                .ok(),
+        };
+
+        if let Err(e) = fs::remove_file(self.job_path(&job.id)) {
+            self.logger
+                .log(
+                    LogLevel::WARN,
+                    &format!("Failed to remove spooled print job {}: {}", job.id, e),
+                )
+                .ok();
+        }
+        self.emit_status(job);
+    }
+
+    fn persist(&self, job: &PrintJob) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(job).map_err(|e| e.to_string())?;
+        fs::write(self.job_path(&job.id), content).map_err(|e| {
+            self.logger
+                .log_error(
+                    &format!("Failed to persist print job {}: {}", job.id, e),
+                    file!(),
+                    "persist",
+                    line!(),
+                )
+                .ok();
+            e.to_string()
+        })
+    }
+
+    fn emit_status(&self, job: &PrintJob) {
+        if let Err(e) = self.app_handle.emit(
+            "print-job-status",
+            PrintJobStatusEvent {
+                job_id: job.id.clone(),
+                status: job.status,
+                attempts: job.attempts,
+                error: job.last_error.clone(),
+                bytes_written: job
+                    .write_failure
+                    .as_ref()
+                    .map_or(0, |failure| failure.bytes_written),
+                write_failure: job.write_failure.clone(),
+            },
+        ) {
+            println!("Failed to emit print-job-status event: {}", e);
+        }
+    }
+
+    fn job_path(&self, job_id: &str) -> PathBuf {
+        self.spool_dir.join(format!("{}.json", job_id))
+    }
+
+    fn lock_queue(&self) -> std::sync::MutexGuard<'_, SpoolQueue> {
+        match self.queue.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                self.logger
+                    .log_error(
+                        "Print spooler mutex was poisoned, recovering",
+                        file!(),
+                        "lock_queue",
+                        line!(),
+                    )
+                    .ok();
+                poisoned.into_inner()
+            }
+        }
+    }
+}
+
+// Load jobs left over from a previous run, oldest first
+fn load_spooled_jobs(spool_dir: &Path, logger: &Arc<Logger>) -> Vec<PrintJob> {
+    let entries = match fs::read_dir(spool_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            logger
+                .log_error(
+                    &format!("Failed to read print spool directory: {}", e),
+                    file!(),
+                    "load_spooled_jobs",
+                    line!(),
+                )
+                .ok();
+            return Vec::new();
+        }
+    };
+
+    let mut jobs: Vec<PrintJob> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| {
+                    serde_json::from_str::<PrintJob>(&content).map_err(|e| e.to_string())
+                });
+            match parsed {
+                Ok(job) => Some(job),
+                Err(e) => {
+                    logger
+                        .log(
+                            LogLevel::WARN,
+                            &format!("Discarding unreadable print job {:?}: {}", path, e),
+                        )
+                        .ok();
+                    fs::remove_file(&path).ok();
+                    None
+                }
+            }
+        })
+        .collect();
+
+    for job in jobs.iter_mut() {
+        // A job that was printing when the app stopped is queued again
+        job.status = PrintJobStatus::Queued;
+    }
+    jobs.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    jobs
+}
+
+#[tauri::command]
+pub fn submit_print_job(
+    print_options: PrintOptions,
+    spooler: State<'_, Arc<PrintSpooler>>,
+) -> Result<String, String> {
+    spooler.submit(print_options)
+}
+
+#[tauri::command]
+pub fn cancel_print_job(job_id: String, spooler: State<'_, Arc<PrintSpooler>>) -> Result<(), String> {
+    spooler.cancel(&job_id)
+}
+
+#[tauri::command]
+pub fn list_print_jobs(spooler: State<'_, Arc<PrintSpooler>>) -> Result<Vec<PrintJob>, String> {
+    Ok(spooler.list())
+}
@@ -1,30 +1,58 @@
+use crate::config_validation::{describe_errors, validate_config};
 use crate::logger::{LogLevel, Logger};
+use crate::paths;
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
-use std::env;
+use serde_json::Value;
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
+/// Version of the configuration file layout written by this build
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Migration steps; entry `n` upgrades a version `n` config to version `n + 1`
+const MIGRATIONS: &[fn(&mut Value)] = &[migrate_v0_normalize_urls, migrate_v1_add_defaults];
+
+const URL_KEYS: &[&str] = &[
+    "primary_server_url",
+    "secondary_server_url",
+    "qr_server_url",
+    "rfid_server_url",
+    "manager_ip_url",
+];
+const QR_CHECK_SUFFIX: &str = "/qrCheck.do";
+
+/// Number of previous config files kept as configuration.dll.bak1 (newest) to .bakN
+const CONFIG_BACKUP_COUNT: usize = 3;
+
+type RecoveryListener = Box<dyn Fn(&ConfigRecovery) + Send>;
+
+lazy_static! {
+    static ref RECOVERY_LISTENER: Mutex<Option<RecoveryListener>> = Mutex::new(None);
+    static ref PENDING_RECOVERY: Mutex<Option<ConfigRecovery>> = Mutex::new(None);
+}
+
 // Provide default for `protocol` in case it's missing from JSON
 fn default_primary_server_url() -> String {
-    "http://k-rsv.snu.ac.kr:8011/NEW_SNU_BOOKING/".to_string()
+    "http://k-rsv.snu.ac.kr:8011/NEW_SNU_BOOKING".to_string()
 }
 
 // Provide default for secondary server URL
 fn default_secondary_server_url() -> String {
-    "http://k-rsv.snu.ac.kr:8012/SEATAPI/".to_string()
+    "http://k-rsv.snu.ac.kr:8012/SEATAPI".to_string()
 }
 
 // Provide default for QR server URL
 fn default_qr_server_url() -> String {
-    "https://libapp.snu.ac.kr/SNU_MOB/qrCheck.do".to_string()
+    "https://libapp.snu.ac.kr/SNU_MOB".to_string()
 }
 
 // Provide default for RFID server URL
 fn default_rfid_server_url() -> String {
-    "https://libapp.snu.ac.kr/SNU_MOB/qrCheck.do".to_string()
+    "https://libapp.snu.ac.kr/SNU_MOB".to_string()
 }
 
 // Provide default for RFID server URL
@@ -98,7 +126,302 @@ fn default_popup_timers() -> Vec<PopupTimer> {
     ]
 }
 
+/// Provide default printer profiles
+fn default_printer_profiles() -> Vec<PrinterProfile> {
+    vec![
+        PrinterProfile {
+            name: "80mm".to_string(),
+            paper_width_mm: 80,
+            dpi: 180,
+            chars_per_line: 42,
+            code_page: 0x0B,
+            default_code_page: 0x00,
+            encoding: "EUC-KR".to_string(),
+            cut: CutStyle::Full,
+            feed_lines_before_cut: 0,
+            transport: PrinterTransport::Serial,
+            roll_length_mm: default_roll_length_mm(),
+            low_paper_threshold_mm: default_low_paper_threshold_mm(),
+            write_chunk_bytes: default_write_chunk_bytes(),
+            write_chunk_delay_ms: default_write_chunk_delay_ms(),
+            job_timeout_secs: default_job_timeout_secs(),
+        },
+        PrinterProfile {
+            name: "58mm".to_string(),
+            paper_width_mm: 58,
+            dpi: 180,
+            chars_per_line: 30,
+            code_page: 0x0B,
+            default_code_page: 0x00,
+            encoding: "EUC-KR".to_string(),
+            cut: CutStyle::Partial,
+            feed_lines_before_cut: 0,
+            transport: PrinterTransport::Serial,
+            roll_length_mm: default_roll_length_mm(),
+            low_paper_threshold_mm: default_low_paper_threshold_mm(),
+            write_chunk_bytes: default_write_chunk_bytes(),
+            write_chunk_delay_ms: default_write_chunk_delay_ms(),
+            job_timeout_secs: default_job_timeout_secs(),
+        },
+    ]
+}
+
+fn default_print_history_max_entries() -> usize {
+    20
+}
+
+fn default_print_history_retention_minutes() -> u64 {
+    60
+}
+
+/// Reprint History Settings
+/// Slips contain personal data, so history is both bounded and short-lived
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PrintHistorySettings {
+    #[serde(default = "default_print_history_max_entries")]
+    pub max_entries: usize, // Number of recent jobs kept
+    #[serde(default = "default_print_history_retention_minutes")]
+    pub retention_minutes: u64, // Jobs older than this are deleted
+}
+
+impl Default for PrintHistorySettings {
+    fn default() -> Self {
+        PrintHistorySettings {
+            max_entries: default_print_history_max_entries(),
+            retention_minutes: default_print_history_retention_minutes(),
+        }
+    }
+}
+
+fn default_presence_min_on_ms() -> u64 {
+    500
+}
+
+fn default_presence_min_off_ms() -> u64 {
+    5_000
+}
+
+fn default_presence_on_level() -> u8 {
+    50
+}
+
+fn default_presence_off_level() -> u8 {
+    20
+}
+
+fn default_presence_smoothing_ms() -> u64 {
+    500
+}
+
+/// Debounce of the raw human sensor signal into presence sessions.
+/// The signal is smoothed into an activity level (0-100); a person arrives once the level
+/// stays at or above `on_level` for `min_on_ms` and leaves once it stays at or below
+/// `off_level` for `min_off_ms`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct PresenceDebounce {
+    #[serde(default = "default_presence_min_on_ms")]
+    pub min_on_ms: u64,
+    #[serde(default = "default_presence_min_off_ms")]
+    pub min_off_ms: u64,
+    #[serde(default = "default_presence_on_level")]
+    pub on_level: u8,
+    #[serde(default = "default_presence_off_level")]
+    pub off_level: u8,
+    #[serde(default = "default_presence_smoothing_ms")]
+    pub smoothing_ms: u64, // Time constant of the activity level; 0 uses raw samples
+}
+
+impl Default for PresenceDebounce {
+    fn default() -> Self {
+        PresenceDebounce {
+            min_on_ms: default_presence_min_on_ms(),
+            min_off_ms: default_presence_min_off_ms(),
+            on_level: default_presence_on_level(),
+            off_level: default_presence_off_level(),
+            smoothing_ms: default_presence_smoothing_ms(),
+        }
+    }
+}
+
+/// Modem status line a sensor can be wired to
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum SensorPin {
+    Cts,
+    Dsr,
+    Dcd,
+    Ri,
+}
+
+/// Modem control line that can power a sensor
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PowerPin {
+    Dtr,
+    Rts,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct SensorInput {
+    pub pin: SensorPin,
+    #[serde(default)]
+    pub inverted: bool, // Active-low sensors report presence by dropping the line
+}
+
+/// How the inputs are combined into one "detected" signal
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CombineRule {
+    #[default]
+    Any,
+    All,
+}
+
+fn default_sensor_inputs() -> Vec<SensorInput> {
+    vec![
+        SensorInput {
+            pin: SensorPin::Cts,
+            inverted: false,
+        },
+        SensorInput {
+            pin: SensorPin::Dsr,
+            inverted: false,
+        },
+    ]
+}
+
+fn default_frame_timeout_ms() -> u64 {
+    2000
+}
+
+/// Where the human sensor's presence signal comes from
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SensorSource {
+    /// Modem status lines of the serial port, read per `inputs` and `combine` (default)
+    #[default]
+    ModemPins,
+    /// Sensor sending frames over the serial port, such as mmWave radar modules.
+    /// Patterns are hex bytes with `??` wildcards, e.g. "F4 F3 F2 F1 ?? ?? 02 AA 01".
+    SerialFrame {
+        present_pattern: String,
+        #[serde(default)]
+        absent_pattern: Option<String>,
+        #[serde(default = "default_frame_timeout_ms")]
+        frame_timeout_ms: u64, // Without a present frame for this long the person counts as gone; 0 waits for the absent pattern
+    },
+    /// Linux GPIO value file, e.g. /sys/class/gpio/gpio17/value
+    SysfsGpio {
+        path: String,
+        #[serde(default)]
+        active_low: bool,
+    },
+    /// Linux GPIO character device line, e.g. line 17 of /dev/gpiochip0
+    GpioChip {
+        chip: String,
+        line: u32,
+        #[serde(default)]
+        active_low: bool,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleDay {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+/// Time window in which the human sensor is switched off or uses other debounce settings.
+/// Times are local "HH:MM"; a window may cross midnight, and equal times cover the whole day.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct SensorScheduleWindow {
+    #[serde(default)]
+    pub name: String,
+    pub start: String,
+    pub end: String,
+    #[serde(default)]
+    pub days: Vec<ScheduleDay>, // Day the window starts on; empty means every day
+    #[serde(default)]
+    pub disabled: bool, // Ignore the sensor during the window
+    #[serde(default)]
+    pub debounce: Option<PresenceDebounce>, // Used instead of the normal debounce settings
+}
+
+/// Settings of the HUMAN_SENSOR serial entry
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct HumanSensorSettings {
+    #[serde(default)]
+    pub source: SensorSource,
+    #[serde(default = "default_sensor_inputs")]
+    pub inputs: Vec<SensorInput>, // Only used by the modem_pins source
+    #[serde(default)]
+    pub combine: CombineRule, // Only used by the modem_pins source
+    #[serde(default)]
+    pub power_pins: Vec<PowerPin>, // Raised when monitoring starts, for sensors powered from the serial port
+    #[serde(default)]
+    pub debounce: PresenceDebounce,
+    #[serde(default)]
+    pub schedule: Vec<SensorScheduleWindow>, // The first matching window applies
+}
+
+impl Default for HumanSensorSettings {
+    fn default() -> Self {
+        HumanSensorSettings {
+            source: SensorSource::default(),
+            inputs: default_sensor_inputs(),
+            combine: CombineRule::default(),
+            power_pins: Vec::new(),
+            debounce: PresenceDebounce::default(),
+            schedule: Vec::new(),
+        }
+    }
+}
+
+fn default_attract_after_secs() -> u64 {
+    120
+}
+
+/// Backend idle handling; session timeouts come from the logout popup timers
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct IdleSettings {
+    #[serde(default = "default_attract_after_secs")]
+    pub attract_after_secs: u64, // Nobody present this long starts attract mode; 0 disables it
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        IdleSettings {
+            attract_after_secs: default_attract_after_secs(),
+        }
+    }
+}
+
+fn default_occupancy_retention_days() -> u64 {
+    90
+}
+
+/// Local store of human sensor sessions used for occupancy statistics
 #[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OccupancySettings {
+    #[serde(default = "default_occupancy_retention_days")]
+    pub retention_days: u64,
+}
+
+impl Default for OccupancySettings {
+    fn default() -> Self {
+        OccupancySettings {
+            retention_days: default_occupancy_retention_days(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct SerialData {
     pub ID: u32,
     pub port: String,
@@ -107,6 +430,87 @@ pub struct SerialData {
     pub stopbit: u32,
     pub databit: u32,
     pub parity: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>, // Printer profile name, only used by printer entries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensor: Option<HumanSensorSettings>, // Only used by the HUMAN_SENSOR entry
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CutStyle {
+    Full,
+    Partial,
+}
+
+fn default_roll_length_mm() -> u32 {
+    100_000 // 100 m roll
+}
+
+fn default_low_paper_threshold_mm() -> u32 {
+    5_000
+}
+
+fn default_raw_tcp_port() -> u16 {
+    9100
+}
+
+fn default_write_chunk_bytes() -> usize {
+    1024
+}
+
+fn default_write_chunk_delay_ms() -> u64 {
+    50
+}
+
+fn default_job_timeout_secs() -> u64 {
+    30
+}
+
+/// Where a printer's byte stream is sent
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PrinterTransport {
+    /// The serial port and baud rate given with the print job (default)
+    #[default]
+    Serial,
+    /// Network printer accepting raw jobs, usually on port 9100
+    Tcp {
+        host: String,
+        #[serde(default = "default_raw_tcp_port")]
+        port: u16,
+    },
+    /// USB printer class device (e.g., /dev/usb/lp0)
+    Usb { device: String },
+    /// Append the byte stream to a file, for testing without a printer
+    File { path: String },
+}
+
+/// Printer Profile
+/// Describes a printer model so slips can be laid out and encoded without code changes
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PrinterProfile {
+    pub name: String,            // Profile name referenced by `SerialData.profile`
+    pub paper_width_mm: u32,     // Paper roll width (58 or 80)
+    pub dpi: u32,                // Print head resolution in dots per inch
+    pub chars_per_line: u32,     // Font A characters that fit on one line
+    pub code_page: u8,           // ESC t code page selected for encoded text
+    pub default_code_page: u8,   // ESC t code page restored after encoded text
+    pub encoding: String,        // Text encoding label (e.g., "EUC-KR")
+    pub cut: CutStyle,           // Cut performed by the `cut` command
+    pub feed_lines_before_cut: u8, // Lines fed before cutting so the last line clears the blade
+    #[serde(default)]
+    pub transport: PrinterTransport, // How the printer is reached
+    #[serde(default = "default_roll_length_mm")]
+    pub roll_length_mm: u32, // Length of a new paper roll
+    #[serde(default = "default_low_paper_threshold_mm")]
+    pub low_paper_threshold_mm: u32, // Warn when the estimated remaining paper drops below this
+    #[serde(default = "default_write_chunk_bytes")]
+    pub write_chunk_bytes: usize, // Largest write sent at once; keep it within the printer's receive buffer
+    #[serde(default = "default_write_chunk_delay_ms")]
+    pub write_chunk_delay_ms: u64, // Pause between chunks so the printer can work through its buffer
+    #[serde(default = "default_job_timeout_secs")]
+    pub job_timeout_secs: u64, // Deadline for sending a whole job
 }
 
 /// Popup Timer Configuration
@@ -120,8 +524,10 @@ pub struct PopupTimer {
     pub state: bool,  // State: true (active) or false (inactive)
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Config {
+    #[serde(default)]
+    pub schema_version: u32, // Missing in files written before versioning, i.e. version 0
     pub machineId: String,
     #[serde(default)]
     pub machineName: String,
@@ -142,23 +548,196 @@ pub struct Config {
     pub popup_timers: Vec<PopupTimer>,
     #[serde(default)]
     pub humanSensorDetection: bool,
+    #[serde(default = "default_printer_profiles")]
+    pub printer_profiles: Vec<PrinterProfile>,
+    #[serde(default)]
+    pub print_history: PrintHistorySettings,
+    #[serde(default)]
+    pub idle: IdleSettings,
+    #[serde(default)]
+    pub occupancy: OccupancySettings,
 }
 
-// Ensure the config file exists, otherwise create a default one
-pub fn ensure_config_exists(logger: Arc<Logger>) -> Result<PathBuf, Box<dyn std::error::Error>> {
-    let app_data_dir = env::var("APPDATA").map_err(|e| {
+/// Configuration written on first run
+pub fn default_config() -> Config {
+    Config {
+        schema_version: CURRENT_SCHEMA_VERSION,
+        machineId: Uuid::new_v4().to_string(),
+        machineName: "SNU KIOSK".to_string(),
+        primary_server_url: default_primary_server_url(),
+        secondary_server_url: default_secondary_server_url(),
+        qr_server_url: default_qr_server_url(),
+        rfid_server_url: default_rfid_server_url(),
+        manager_ip_url: default_manager_ip_url(),
+        kiosk_mode: true,
+        debug_mode: false,
+        humanSensorDetection: true,
+        serialdata: vec![
+            SerialData {
+                ID: 1,
+                port: "".to_string(),
+                baudrate: 0,
+                name: "RFID".to_string(),
+                stopbit: 1,
+                databit: 8,
+                parity: 0,
+                profile: None,
+                sensor: None,
+            },
+            SerialData {
+                ID: 2,
+                port: "".to_string(),
+                baudrate: 0,
+                name: "QR".to_string(),
+                stopbit: 1,
+                databit: 8,
+                parity: 0,
+                profile: None,
+                sensor: None,
+            },
+            SerialData {
+                ID: 3,
+                port: "".to_string(),
+                baudrate: 0,
+                name: "BARCODE".to_string(),
+                stopbit: 1,
+                databit: 8,
+                parity: 0,
+                profile: None,
+                sensor: None,
+            },
+            SerialData {
+                ID: 4,
+                port: "".to_string(),
+                baudrate: 0,
+                name: "BIOMATRIC".to_string(),
+                stopbit: 1,
+                databit: 8,
+                parity: 0,
+                profile: None,
+                sensor: None,
+            },
+            SerialData {
+                ID: 5,
+                port: "".to_string(),
+                baudrate: 0,
+                name: "FACE".to_string(),
+                stopbit: 1,
+                databit: 8,
+                parity: 0,
+                profile: None,
+                sensor: None,
+            },
+            SerialData {
+                ID: 6,
+                port: "".to_string(),
+                baudrate: 0,
+                name: "PRINTER".to_string(),
+                stopbit: 1,
+                databit: 8,
+                parity: 0,
+                profile: Some("80mm".to_string()),
+                sensor: None,
+            },
+            SerialData {
+                ID: 7,
+                port: "".to_string(),
+                baudrate: 9600,
+                name: "HUMAN_SENSOR".to_string(),
+                stopbit: 1,
+                databit: 8,
+                parity: 0,
+                profile: None,
+                sensor: Some(HumanSensorSettings::default()),
+            },
+        ],
+        popup_timers: vec![
+            PopupTimer {
+                ID: 1,
+                name: "KEYBOARD TIMER".to_string(),
+                time: Some(30),
+                state: true,
+            },
+            PopupTimer {
+                ID: 2,
+                name: "HISTORY PAGE TIMER".to_string(),
+                time: Some(15),
+                state: true,
+            },
+            PopupTimer {
+                ID: 3,
+                name: "SUCCESS TIMER".to_string(),
+                time: Some(2),
+                state: true,
+            },
+            PopupTimer {
+                ID: 4,
+                name: "ERROR TIMER".to_string(),
+                time: Some(3),
+                state: true,
+            },
+            PopupTimer {
+                ID: 5,
+                name: "WARNING TIMER".to_string(),
+                time: Some(3),
+                state: true,
+            },
+            PopupTimer {
+                ID: 6,
+                name: "PRINTER TIMER".to_string(),
+                time: Some(5),
+                state: true,
+            },
+            PopupTimer {
+                ID: 7,
+                name: "LOG OUT TIMER".to_string(),
+                time: Some(180),
+                state: true,
+            },
+            PopupTimer {
+                ID: 8,
+                name: "LOG OUT FLOOR TIMER".to_string(),
+                time: Some(180),
+                state: true,
+            },
+            PopupTimer {
+                ID: 9,
+                name: "SESSION TIMER REMINDER".to_string(),
+                time: Some(60),
+                state: true,
+            },
+            PopupTimer {
+                ID: 10,
+                name: "RESET TIMER ON TOUCH".to_string(),
+                time: None,
+                state: false,
+            },
+        ],
+        printer_profiles: default_printer_profiles(),
+        print_history: PrintHistorySettings::default(),
+        idle: IdleSettings::default(),
+        occupancy: OccupancySettings::default(),
+    }
+}
+
+// Resolve the directory holding the config file and other persisted kiosk state
+pub fn get_config_dir(logger: Arc<Logger>) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    paths::app_home().map_err(|e| {
         logger
             .log_error(
-                &format!("Failed to get APPDATA: {}", e),
+                &format!("Failed to resolve app data directory: {}", e),
                 file!(),
-                "ensure_config_exists",
+                "get_config_dir",
                 line!(),
             )
-            .unwrap();
-        e
-    })?;
+            .ok();
+        e.into()
+    })
+}
 
-    let config_dir = PathBuf::from(app_data_dir).join("wise-neosco-kiosk-snu");
+// Ensure the config file exists, otherwise create a default one
+pub fn ensure_config_exists(logger: Arc<Logger>) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let config_dir = get_config_dir(Arc::clone(&logger))?;
     let config_file_path = config_dir.join("configuration.dll");
 
     if !config_dir.exists() {
@@ -176,206 +755,578 @@ pub fn ensure_config_exists(logger: Arc<Logger>) -> Result<PathBuf, Box<dyn std:
     }
 
     if !config_file_path.exists() {
-        let default_config = Config {
-            machineId: Uuid::new_v4().to_string(),
-            machineName: "SNU KIOSK".to_string(),
-            primary_server_url: "http://k-rsv.snu.ac.kr:8011/NEW_SNU_BOOKING".to_string(),
-            secondary_server_url: "http://k-rsv.snu.ac.kr:8012/SEATAPI".to_string(),
-            qr_server_url: "https://libapp.snu.ac.kr/SNU_MOB".to_string(),
-            rfid_server_url: "https://libapp.snu.ac.kr/SNU_MOB".to_string(),
-            manager_ip_url: "http://192.168.1.3:5841".to_string(),
-            kiosk_mode: true,
-            debug_mode: false,
-            humanSensorDetection: true,
-            serialdata: vec![
-                SerialData {
-                    ID: 1,
-                    port: "".to_string(),
-                    baudrate: 0,
-                    name: "RFID".to_string(),
-                    stopbit: 1,
-                    databit: 8,
-                    parity: 0,
-                },
-                SerialData {
-                    ID: 2,
-                    port: "".to_string(),
-                    baudrate: 0,
-                    name: "QR".to_string(),
-                    stopbit: 1,
-                    databit: 8,
-                    parity: 0,
-                },
-                SerialData {
-                    ID: 3,
-                    port: "".to_string(),
-                    baudrate: 0,
-                    name: "BARCODE".to_string(),
-                    stopbit: 1,
-                    databit: 8,
-                    parity: 0,
-                },
-                SerialData {
-                    ID: 4,
-                    port: "".to_string(),
-                    baudrate: 0,
-                    name: "BIOMATRIC".to_string(),
-                    stopbit: 1,
-                    databit: 8,
-                    parity: 0,
-                },
-                SerialData {
-                    ID: 5,
-                    port: "".to_string(),
-                    baudrate: 0,
-                    name: "FACE".to_string(),
-                    stopbit: 1,
-                    databit: 8,
-                    parity: 0,
-                },
-                SerialData {
-                    ID: 6,
-                    port: "".to_string(),
-                    baudrate: 0,
-                    name: "PRINTER".to_string(),
-                    stopbit: 1,
-                    databit: 8,
-                    parity: 0,
-                },
-                SerialData {
-                    ID: 7,
-                    port: "".to_string(),
-                    baudrate: 9600,
-                    name: "HUMAN_SENSOR".to_string(),
-                    stopbit: 1,
-                    databit: 8,
-                    parity: 0,
-                },
-            ],
-            popup_timers: vec![
-                PopupTimer {
-                    ID: 1,
-                    name: "KEYBOARD TIMER".to_string(),
-                    time: Some(30),
-                    state: true,
-                },
-                PopupTimer {
-                    ID: 2,
-                    name: "HISTORY PAGE TIMER".to_string(),
-                    time: Some(15),
-                    state: true,
-                },
-                PopupTimer {
-                    ID: 3,
-                    name: "SUCCESS TIMER".to_string(),
-                    time: Some(2),
-                    state: true,
-                },
-                PopupTimer {
-                    ID: 4,
-                    name: "ERROR TIMER".to_string(),
-                    time: Some(3),
-                    state: true,
-                },
-                PopupTimer {
-                    ID: 5,
-                    name: "WARNING TIMER".to_string(),
-                    time: Some(3),
-                    state: true,
-                },
-                PopupTimer {
-                    ID: 6,
-                    name: "PRINTER TIMER".to_string(),
-                    time: Some(5),
-                    state: true,
-                },
-                PopupTimer {
-                    ID: 7,
-                    name: "LOG OUT TIMER".to_string(),
-                    time: Some(180),
-                    state: true,
-                },
-                PopupTimer {
-                    ID: 8,
-                    name: "LOG OUT FLOOR TIMER".to_string(),
-                    time: Some(180),
-                    state: true,
-                },
-                PopupTimer {
-                    ID: 9,
-                    name: "SESSION TIMER REMINDER".to_string(),
-                    time: Some(60),
-                    state: true,
-                },
-                PopupTimer {
-                    ID: 10,
-                    name: "RESET TIMER ON TOUCH".to_string(),
-                    time: None,
-                    state: false,
-                },
-            ],
-        };
+        let default_config = default_config();
+
+        write_config_file(
+            &logger,
+            &config_file_path,
+            &serde_json::to_string_pretty(&default_config)?,
+        )?;
+
+        logger
+            .log(LogLevel::INFO, "Default config file created successfully")
+            .unwrap();
+    }
+
+    Ok(config_file_path)
+}
+
+/// Base URLs are stored without a trailing slash, and the QR and RFID URLs without the
+/// `/qrCheck.do` endpoint the frontend appends itself
+pub fn normalize_url(key: &str, url: &str) -> String {
+    let mut url = url.trim().trim_end_matches('/');
+    if key == "qr_server_url" || key == "rfid_server_url" {
+        url = url.strip_suffix(QR_CHECK_SUFFIX).unwrap_or(url);
+        url = url.trim_end_matches('/');
+    }
+    url.to_string()
+}
+
+fn schema_version(config: &Value) -> u32 {
+    config
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as u32
+}
+
+// v0 -> v1: early builds wrote URLs with and without trailing slashes and endpoint suffixes
+fn migrate_v0_normalize_urls(config: &mut Value) {
+    let Some(config) = config.as_object_mut() else {
+        return;
+    };
+    for key in URL_KEYS {
+        if let Some(Value::String(url)) = config.get_mut(*key) {
+            *url = normalize_url(key, url);
+        }
+    }
+}
+
+// Defaults as of schema version 2. Kept frozen so that settings added later are not
+// written by this step; the machine ID is left out because it can't have a default, and
+// humanSensorDetection because files without it have always meant detection off.
+const V2_DEFAULTS: &str = r#"{
+  "debug_mode": false,
+  "idle": {
+    "attract_after_secs": 120
+  },
+  "kiosk_mode": true,
+  "machineName": "SNU KIOSK",
+  "manager_ip_url": "http://192.168.1.3:5841",
+  "occupancy": {
+    "retention_days": 90
+  },
+  "popup_timers": [
+    {
+      "ID": 1,
+      "name": "KEYBOARD TIMER",
+      "state": true,
+      "time": 30
+    },
+    {
+      "ID": 2,
+      "name": "HISTORY PAGE TIMER",
+      "state": true,
+      "time": 15
+    },
+    {
+      "ID": 3,
+      "name": "SUCCESS TIMER",
+      "state": true,
+      "time": 2
+    },
+    {
+      "ID": 4,
+      "name": "ERROR TIMER",
+      "state": true,
+      "time": 3
+    },
+    {
+      "ID": 5,
+      "name": "WARNING TIMER",
+      "state": true,
+      "time": 3
+    },
+    {
+      "ID": 6,
+      "name": "PRINTER TIMER",
+      "state": true,
+      "time": 5
+    },
+    {
+      "ID": 7,
+      "name": "LOG OUT TIMER",
+      "state": true,
+      "time": 180
+    },
+    {
+      "ID": 8,
+      "name": "LOG OUT FLOOR TIMER",
+      "state": true,
+      "time": 180
+    },
+    {
+      "ID": 9,
+      "name": "SESSION TIMER REMINDER",
+      "state": true,
+      "time": 60
+    },
+    {
+      "ID": 10,
+      "name": "RESET TIMER ON TOUCH",
+      "state": false
+    }
+  ],
+  "primary_server_url": "http://k-rsv.snu.ac.kr:8011/NEW_SNU_BOOKING",
+  "print_history": {
+    "max_entries": 20,
+    "retention_minutes": 60
+  },
+  "printer_profiles": [
+    {
+      "chars_per_line": 42,
+      "code_page": 11,
+      "cut": "full",
+      "default_code_page": 0,
+      "dpi": 180,
+      "encoding": "EUC-KR",
+      "feed_lines_before_cut": 0,
+      "job_timeout_secs": 30,
+      "low_paper_threshold_mm": 5000,
+      "name": "80mm",
+      "paper_width_mm": 80,
+      "roll_length_mm": 100000,
+      "transport": {
+        "type": "serial"
+      },
+      "write_chunk_bytes": 1024,
+      "write_chunk_delay_ms": 50
+    },
+    {
+      "chars_per_line": 30,
+      "code_page": 11,
+      "cut": "partial",
+      "default_code_page": 0,
+      "dpi": 180,
+      "encoding": "EUC-KR",
+      "feed_lines_before_cut": 0,
+      "job_timeout_secs": 30,
+      "low_paper_threshold_mm": 5000,
+      "name": "58mm",
+      "paper_width_mm": 58,
+      "roll_length_mm": 100000,
+      "transport": {
+        "type": "serial"
+      },
+      "write_chunk_bytes": 1024,
+      "write_chunk_delay_ms": 50
+    }
+  ],
+  "qr_server_url": "https://libapp.snu.ac.kr/SNU_MOB",
+  "rfid_server_url": "https://libapp.snu.ac.kr/SNU_MOB",
+  "secondary_server_url": "http://k-rsv.snu.ac.kr:8012/SEATAPI",
+  "serialdata": [
+    {
+      "ID": 1,
+      "baudrate": 0,
+      "databit": 8,
+      "name": "RFID",
+      "parity": 0,
+      "port": "",
+      "stopbit": 1
+    },
+    {
+      "ID": 2,
+      "baudrate": 0,
+      "databit": 8,
+      "name": "QR",
+      "parity": 0,
+      "port": "",
+      "stopbit": 1
+    },
+    {
+      "ID": 3,
+      "baudrate": 0,
+      "databit": 8,
+      "name": "BARCODE",
+      "parity": 0,
+      "port": "",
+      "stopbit": 1
+    },
+    {
+      "ID": 4,
+      "baudrate": 0,
+      "databit": 8,
+      "name": "BIOMATRIC",
+      "parity": 0,
+      "port": "",
+      "stopbit": 1
+    },
+    {
+      "ID": 5,
+      "baudrate": 0,
+      "databit": 8,
+      "name": "FACE",
+      "parity": 0,
+      "port": "",
+      "stopbit": 1
+    },
+    {
+      "ID": 6,
+      "baudrate": 0,
+      "databit": 8,
+      "name": "PRINTER",
+      "parity": 0,
+      "port": "",
+      "profile": "80mm",
+      "stopbit": 1
+    },
+    {
+      "ID": 7,
+      "baudrate": 9600,
+      "databit": 8,
+      "name": "HUMAN_SENSOR",
+      "parity": 0,
+      "port": "",
+      "sensor": {
+        "combine": "any",
+        "debounce": {
+          "min_off_ms": 5000,
+          "min_on_ms": 500,
+          "off_level": 20,
+          "on_level": 50,
+          "smoothing_ms": 500
+        },
+        "inputs": [
+          {
+            "inverted": false,
+            "pin": "CTS"
+          },
+          {
+            "inverted": false,
+            "pin": "DSR"
+          }
+        ],
+        "power_pins": [],
+        "schedule": [],
+        "source": {
+          "type": "modem_pins"
+        }
+      },
+      "stopbit": 1
+    }
+  ]
+}"#;
+
+// v1 -> v2: write out settings added since, so they appear in the file with their defaults
+fn migrate_v1_add_defaults(config: &mut Value) {
+    let Ok(Value::Object(defaults)) = serde_json::from_str::<Value>(V2_DEFAULTS) else {
+        return;
+    };
+    let Some(config) = config.as_object_mut() else {
+        return;
+    };
+
+    // Popup timers are matched by name, keeping the IDs already in the file
+    if let (Some(Value::Array(timers)), Some(Value::Array(default_timers))) =
+        (config.get_mut("popup_timers"), defaults.get("popup_timers"))
+    {
+        let mut next_id = timers
+            .iter()
+            .filter_map(|timer| timer.get("ID").and_then(Value::as_u64))
+            .max()
+            .unwrap_or(0);
+        for default_timer in default_timers {
+            let name = default_timer.get("name");
+            if !timers.iter().any(|timer| timer.get("name") == name) {
+                next_id += 1;
+                let mut timer = default_timer.clone();
+                timer["ID"] = Value::from(next_id);
+                timers.push(timer);
+            }
+        }
+    }
+
+    if let Some(Value::Array(entries)) = config.get_mut("serialdata") {
+        let sensor = defaults
+            .get("serialdata")
+            .and_then(Value::as_array)
+            .and_then(|entries| {
+                entries
+                    .iter()
+                    .find(|entry| entry.get("name").and_then(Value::as_str) == Some("HUMAN_SENSOR"))
+            })
+            .and_then(|entry| entry.get("sensor"))
+            .cloned()
+            .unwrap_or(Value::Null);
+        for entry in entries.iter_mut().filter_map(Value::as_object_mut) {
+            if entry.get("name").and_then(Value::as_str) == Some("HUMAN_SENSOR") {
+                entry.entry("sensor").or_insert_with(|| sensor.clone());
+            }
+        }
+    }
+
+    for (key, default) in defaults {
+        config.entry(key).or_insert(default);
+    }
+}
 
-        let mut file = File::create(&config_file_path).map_err(|e| {
+/// Upgrade a parsed config from `from` to the current schema version, one step at a time
+fn migrate_config(config: &mut Value, from: u32) {
+    for (index, step) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+        step(config);
+        if let Some(config) = config.as_object_mut() {
+            config.insert("schema_version".to_string(), Value::from(index + 1));
+        }
+    }
+}
+
+// Migrate an old config file in place, keeping a copy of the original next to it
+fn migrate_config_file(
+    logger: &Arc<Logger>,
+    config_file_path: &Path,
+    original: &str,
+    config: &mut Value,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let from = schema_version(config);
+    let backup_path = config_file_path.with_extension(format!("dll.v{}.bak", from));
+    // A backup left by an interrupted migration already holds the original
+    if !backup_path.exists() {
+        fs::write(&backup_path, original).map_err(|e| {
             logger
                 .log_error(
-                    &format!("Failed to create config file: {}", e),
+                    &format!("Failed to back up config file before migration: {}", e),
                     file!(),
-                    "ensure_config_exists",
+                    "migrate_config_file",
                     line!(),
                 )
-                .unwrap();
+                .ok();
             e
         })?;
+    }
 
-        file.write_all(serde_json::to_string_pretty(&default_config)?.as_bytes())
-            .map_err(|e| {
-                logger
-                    .log_error(
-                        &format!("Failed to write to config file: {}", e),
-                        file!(),
-                        "ensure_config_exists",
-                        line!(),
-                    )
-                    .unwrap();
-                e
-            })?;
+    migrate_config(config, from);
+    write_config_file(
+        logger,
+        config_file_path,
+        &serde_json::to_string_pretty(config)?,
+    )?;
 
-        logger
-            .log(LogLevel::INFO, "Default config file created successfully")
-            .unwrap();
+    logger
+        .log(
+            LogLevel::INFO,
+            &format!(
+                "Config migrated from schema version {} to {}, original kept at {}",
+                from,
+                CURRENT_SCHEMA_VERSION,
+                backup_path.display()
+            ),
+        )
+        .ok();
+    Ok(())
+}
+
+/// Sent to the recovery listener when an unreadable config file was replaced
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigRecovery {
+    pub error: String,                 // Why the config file could not be read
+    pub restored_from: Option<String>, // Backup that was restored, None when defaults were used
+}
+
+/// Register the callback run when `read_config_file` recovers from a broken file.
+/// A recovery that happened before registration is delivered right away.
+pub fn on_config_recovered(listener: impl Fn(&ConfigRecovery) + Send + 'static) {
+    if let Some(recovery) = PENDING_RECOVERY.lock().ok().and_then(|mut p| p.take()) {
+        listener(&recovery);
+    }
+    if let Ok(mut slot) = RECOVERY_LISTENER.lock() {
+        *slot = Some(Box::new(listener));
     }
+}
 
-    Ok(config_file_path)
+fn notify_recovery(recovery: ConfigRecovery) {
+    let listener = RECOVERY_LISTENER.lock();
+    match listener.as_ref().ok().and_then(|slot| slot.as_ref()) {
+        Some(listener) => listener(&recovery),
+        None => {
+            if let Ok(mut pending) = PENDING_RECOVERY.lock() {
+                *pending = Some(recovery);
+            }
+        }
+    }
 }
 
-// Read the config file
-pub fn read_config_file(logger: Arc<Logger>) -> Result<Config, Box<dyn std::error::Error>> {
-    let config_file_path = ensure_config_exists(Arc::clone(&logger))?;
+fn backup_path(config_file_path: &Path, index: usize) -> PathBuf {
+    config_file_path.with_extension(format!("dll.bak{}", index))
+}
+
+// Parse config file content, applying schema migrations in memory only
+fn parse_config(content: &str) -> Result<Config, serde_json::Error> {
+    let mut value: Value = serde_json::from_str(content)?;
+    let version = schema_version(&value);
+    migrate_config(&mut value, version);
+    serde_json::from_value(value)
+}
+
+// Shift the backups up by one and copy the current file in as the newest.
+// A file that doesn't parse is not kept, so it can't push out a good backup.
+fn rotate_backups(config_file_path: &Path) -> std::io::Result<()> {
+    let is_good = fs::read_to_string(config_file_path)
+        .map(|content| parse_config(&content).is_ok())
+        .unwrap_or(false);
+    if !is_good {
+        return Ok(());
+    }
 
-    let config_content = fs::read_to_string(&config_file_path).map_err(|e| {
+    for index in (1..CONFIG_BACKUP_COUNT).rev() {
+        let from = backup_path(config_file_path, index);
+        if from.exists() {
+            fs::rename(&from, backup_path(config_file_path, index + 1))?;
+        }
+    }
+    fs::copy(config_file_path, backup_path(config_file_path, 1))?;
+    Ok(())
+}
+
+// Write the config file so that a power cut leaves either the old or the new
+// content: write a temp file, flush it to disk, then rename it over the original
+fn write_config_file(
+    logger: &Arc<Logger>,
+    config_file_path: &Path,
+    content: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let temp_path = config_file_path.with_extension("dll.tmp");
+    let result = rotate_backups(config_file_path).and_then(|_| {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&temp_path, config_file_path)?;
+        // Persist the rename itself; directories can't be opened this way on Windows
+        #[cfg(unix)]
+        if let Some(dir) = config_file_path.parent() {
+            File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    });
+
+    result.map_err(|e| {
+        fs::remove_file(&temp_path).ok();
         logger
             .log_error(
-                &format!("Failed to read config file: {}", e),
+                &format!("Failed to write config file: {}", e),
                 file!(),
-                "read_config_file",
+                "write_config_file",
                 line!(),
             )
-            .unwrap();
-        e
-    })?;
+            .ok();
+        e.into()
+    })
+}
+
+// Replace an unreadable config file with the newest good backup, or with defaults
+fn recover_config_file(
+    logger: &Arc<Logger>,
+    config_file_path: &Path,
+    error: String,
+) -> Result<Config, Box<dyn std::error::Error>> {
+    // Keep the broken file for inspection
+    fs::copy(
+        config_file_path,
+        config_file_path.with_extension("dll.corrupt"),
+    )
+    .ok();
+
+    let restored = (1..=CONFIG_BACKUP_COUNT).find_map(|index| {
+        let path = backup_path(config_file_path, index);
+        let config = parse_config(&fs::read_to_string(&path).ok()?).ok()?;
+        Some((config, path))
+    });
+    let (config, restored_from) = match restored {
+        Some((config, path)) => (config, Some(path.display().to_string())),
+        None => (default_config(), None),
+    };
+
+    write_config_file(
+        logger,
+        config_file_path,
+        &serde_json::to_string_pretty(&config)?,
+    )?;
+    logger
+        .log(
+            LogLevel::WARN,
+            &format!(
+                "Config file could not be read ({}), restored from {}",
+                error,
+                restored_from.as_deref().unwrap_or("defaults")
+            ),
+        )
+        .ok();
+
+    notify_recovery(ConfigRecovery {
+        error,
+        restored_from,
+    });
+    Ok(config)
+}
+
+fn load_config_file(
+    logger: &Arc<Logger>,
+    config_file_path: &Path,
+) -> Result<Config, Box<dyn std::error::Error>> {
+    let config_content = fs::read_to_string(config_file_path)?;
+    let mut value: Value = serde_json::from_str(&config_content)?;
 
-    let config: Config = serde_json::from_str(&config_content).map_err(|e| {
+    let version = schema_version(&value);
+    if version < CURRENT_SCHEMA_VERSION {
+        migrate_config_file(logger, config_file_path, &config_content, &mut value)?;
+    } else if version > CURRENT_SCHEMA_VERSION {
         logger
-            .log_error(
-                &format!("Failed to parse config file: {}", e),
-                file!(),
-                "read_config_file",
-                line!(),
+            .log(
+                LogLevel::WARN,
+                &format!(
+                    "Config schema version {} is newer than this build supports ({})",
+                    version, CURRENT_SCHEMA_VERSION
+                ),
             )
-            .unwrap();
-        e
-    })?;
+            .ok();
+    }
+
+    Ok(serde_json::from_value(value)?)
+}
+
+// Whether a load error means the file content is broken, rather than that it couldn't be read
+fn is_damaged_config(error: &(dyn std::error::Error + 'static)) -> bool {
+    if error.is::<serde_json::Error>() {
+        return true;
+    }
+    // Not valid UTF-8
+    error
+        .downcast_ref::<std::io::Error>()
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::InvalidData)
+}
+
+// Read the config file, recovering from a backup if it is damaged
+pub fn read_config_file(logger: Arc<Logger>) -> Result<Config, Box<dyn std::error::Error>> {
+    let config_file_path = ensure_config_exists(Arc::clone(&logger))?;
+
+    let config = match load_config_file(&logger, &config_file_path) {
+        Ok(config) => config,
+        Err(e) => {
+            logger
+                .log_error(
+                    &format!("Failed to read config file: {}", e),
+                    file!(),
+                    "read_config_file",
+                    line!(),
+                )
+                .ok();
+            if !is_damaged_config(e.as_ref()) {
+                // e.g. the file is locked or not readable; it may be fine, so leave it alone
+                return Err(e);
+            }
+            recover_config_file(&logger, &config_file_path, e.to_string())?
+        }
+    };
 
     logger
         .log(LogLevel::INFO, "Config file read successfully")
@@ -383,101 +1334,404 @@ pub fn read_config_file(logger: Arc<Logger>) -> Result<Config, Box<dyn std::erro
     Ok(config)
 }
 
-pub fn update_config_key(
-    logger: Arc<Logger>,
-    key: &str,
-    value: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let config_file_path = ensure_config_exists(Arc::clone(&logger))?;
-    let mut config = read_config_file(Arc::clone(&logger))?;
-
-    match key {
-        "primary_server_url" => config.primary_server_url = value.to_string(),
-        "secondary_server_url" => config.secondary_server_url = value.to_string(),
-        "qr_server_url" => config.qr_server_url = value.to_string(),
-        "rfid_server_url" => config.rfid_server_url = value.to_string(),
-        "manager_ip_url" => config.manager_ip_url = value.to_string(),
-        "machineName" => config.machineName = value.to_string(),
-        "serialdata" => {
-            let updated_serialdata: Vec<SerialData> = serde_json::from_str(value).map_err(|e| {
-                logger
-                    .log_error(
-                        &format!("Failed to parse serialdata JSON: {}", e),
-                        file!(),
-                        "update_config_key",
-                        line!(),
-                    )
-                    .unwrap();
-                e
-            })?;
-            config.serialdata = updated_serialdata;
+/// Turn the key and string value sent by the old `update_config_key` command into a merge patch
+pub fn key_patch(key: &str, value: &str) -> Result<Value, String> {
+    let value = match key {
+        "machineName" => Value::String(value.to_string()),
+        _ if URL_KEYS.contains(&key) => Value::String(value.to_string()),
+        "serialdata" => serde_json::from_str::<Vec<SerialData>>(value)
+            .and_then(serde_json::to_value)
+            .map_err(|e| format!("Failed to parse serialdata JSON: {}", e))?,
+        "kiosk_mode" | "humanSensorDetection" => Value::Bool(
+            value
+                .parse::<bool>()
+                .map_err(|e| format!("Failed to parse {} value: {}", key, e))?,
+        ),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+
+    let mut patch = serde_json::Map::new();
+    patch.insert(key.to_string(), value);
+    Ok(Value::Object(patch))
+}
+
+/// Result of a config patch: the stored config and the paths that changed,
+/// e.g. "kiosk_mode" or "serialdata[1].port"
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigUpdate {
+    pub config: Config,
+    pub changed: Vec<String>,
+}
+
+// Apply an RFC 7396 JSON merge patch: objects merge, null removes, anything else replaces
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(serde_json::Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
         }
-        "kiosk_mode" => {
-            config.kiosk_mode = value.parse::<bool>().map_err(|e| {
-                logger
-                    .log_error(
-                        &format!("Failed to parse kiosk_mode value: {}", e),
-                        file!(),
-                        "update_config_key",
-                        line!(),
-                    )
-                    .unwrap();
-                e
-            })?;
+    }
+}
+
+fn join_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+// Find a patched key that the config doesn't have, which serde would drop silently
+fn unknown_path(patch: &Value, config: &Value, parent: &str) -> Option<String> {
+    let (Value::Object(patch), Value::Object(config)) = (patch, config) else {
+        return None;
+    };
+    patch.iter().find_map(|(key, value)| match config.get(key) {
+        Some(existing) => unknown_path(value, existing, &join_path(parent, key)),
+        None if value.is_null() => None,
+        None => Some(join_path(parent, key)),
+    })
+}
+
+// Collect the paths of the leaves that differ, in the style used by config validation
+fn changed_paths(before: &Value, after: &Value, path: &str, changed: &mut Vec<String>) {
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            for (key, value) in after {
+                let field = join_path(path, key);
+                match before.get(key) {
+                    Some(old) => changed_paths(old, value, &field, changed),
+                    None => changed.push(field),
+                }
+            }
+            for key in before.keys().filter(|key| !after.contains_key(*key)) {
+                changed.push(join_path(path, key));
+            }
         }
-        "humanSensorDetection" => {
-            config.humanSensorDetection = value.parse::<bool>().map_err(|e| {
-                logger
-                    .log_error(
-                        &format!("Failed to parse humanSensorDetection value: {}", e),
-                        file!(),
-                        "update_config_key",
-                        line!(),
-                    )
-                    .unwrap();
-                e
-            })?;
+        (Value::Array(before), Value::Array(after)) => {
+            for index in 0..before.len().max(after.len()) {
+                let field = format!("{}[{}]", path, index);
+                match (before.get(index), after.get(index)) {
+                    (Some(old), Some(new)) => changed_paths(old, new, &field, changed),
+                    _ => changed.push(field),
+                }
+            }
         }
+        _ if before != after => changed.push(path.to_string()),
+        _ => {}
+    }
+}
 
-        _ => {
-            logger
-                .log(LogLevel::WARN, &format!("Unknown key: {}", key))
-                .unwrap();
-            return Ok(());
-        }
+/// Apply a merge patch to a config and validate the result without saving it
+pub fn patch_config(current: &Config, patch: &Value) -> Result<ConfigUpdate, String> {
+    if !patch.is_object() {
+        return Err("Config patch must be a JSON object".to_string());
     }
 
-    let updated_content = serde_json::to_string_pretty(&config).map_err(|e| {
-        logger
-            .log_error(
-                &format!("Failed to serialize config: {}", e),
-                file!(),
-                "update_config_key",
-                line!(),
-            )
-            .unwrap();
-        e
-    })?;
+    let before = serde_json::to_value(current).map_err(|e| e.to_string())?;
+    let mut merged = before.clone();
+    merge_patch(&mut merged, patch);
+    migrate_v0_normalize_urls(&mut merged);
 
-    let mut file = File::create(config_file_path).map_err(|e| {
-        logger
-            .log_error(
-                &format!("Failed to write config file: {}", e),
-                file!(),
-                "update_config_key",
-                line!(),
-            )
+    let mut config: Config =
+        serde_json::from_value(merged).map_err(|e| format!("Invalid configuration: {}", e))?;
+    config.schema_version = CURRENT_SCHEMA_VERSION;
+    let after = serde_json::to_value(&config).map_err(|e| e.to_string())?;
+
+    if let Some(path) = unknown_path(patch, &after, "") {
+        return Err(format!("Unknown config key: {}", path));
+    }
+    let errors = validate_config(&config);
+    if !errors.is_empty() {
+        return Err(describe_errors(&errors));
+    }
+
+    let mut changed = Vec::new();
+    changed_paths(&before, &after, "", &mut changed);
+    Ok(ConfigUpdate { config, changed })
+}
+
+/// Replace `current` with the full config in `content`, as read back from the
+/// config file. Settings missing from the file fall back to their defaults.
+pub fn reload_config(current: &Config, content: &str) -> Result<ConfigUpdate, String> {
+    let mut config = parse_config(content).map_err(|e| format!("Invalid configuration: {}", e))?;
+    config.schema_version = CURRENT_SCHEMA_VERSION;
+    let errors = validate_config(&config);
+    if !errors.is_empty() {
+        return Err(describe_errors(&errors));
+    }
+
+    let before = serde_json::to_value(current).map_err(|e| e.to_string())?;
+    let after = serde_json::to_value(&config).map_err(|e| e.to_string())?;
+    let mut changed = Vec::new();
+    changed_paths(&before, &after, "", &mut changed);
+    Ok(ConfigUpdate { config, changed })
+}
+
+/// Write a config to the config file, replacing it atomically
+pub fn save_config(
+    logger: &Arc<Logger>,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config_file_path = ensure_config_exists(Arc::clone(logger))?;
+    write_config_file(
+        logger,
+        &config_file_path,
+        &serde_json::to_string_pretty(config)?,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn version_0_config() -> Value {
+        json!({
+            "machineId": "kiosk-1",
+            "machineName": "Main hall",
+            "primary_server_url": "http://k-rsv.snu.ac.kr:8011/NEW_SNU_BOOKING/",
+            "secondary_server_url": "http://k-rsv.snu.ac.kr:8012/SEATAPI/",
+            "qr_server_url": "https://libapp.snu.ac.kr/SNU_MOB/qrCheck.do",
+            "rfid_server_url": " https://libapp.snu.ac.kr/SNU_MOB/qrCheck.do/ ",
+            "manager_ip_url": "http://192.168.1.3:5841",
+            "kiosk_mode": true,
+            "debug_mode": false,
+            "humanSensorDetection": true,
+            "serialdata": [
+                {"ID": 1, "port": "COM3", "baudrate": 9600, "name": "QR", "stopbit": 1, "databit": 8, "parity": 0},
+                {"ID": 2, "port": "COM4", "baudrate": 9600, "name": "HUMAN_SENSOR", "stopbit": 1, "databit": 8, "parity": 0}
+            ],
+            "popup_timers": [
+                {"ID": 1, "name": "KEYBOARD TIMER", "time": 45, "state": true},
+                {"ID": 7, "name": "LOG OUT TIMER", "time": 120, "state": false}
+            ]
+        })
+    }
+
+    #[test]
+    fn normalize_url_strips_trailing_slashes() {
+        assert_eq!(
+            normalize_url("primary_server_url", " http://host:8011/BOOKING// "),
+            "http://host:8011/BOOKING"
+        );
+        assert_eq!(
+            normalize_url("manager_ip_url", "http://192.168.1.3:5841"),
+            "http://192.168.1.3:5841"
+        );
+    }
+
+    #[test]
+    fn normalize_url_strips_qr_check_endpoint_only_from_qr_and_rfid() {
+        assert_eq!(
+            normalize_url("qr_server_url", "https://host/SNU_MOB/qrCheck.do"),
+            "https://host/SNU_MOB"
+        );
+        assert_eq!(
+            normalize_url("rfid_server_url", "https://host/SNU_MOB/qrCheck.do/"),
+            "https://host/SNU_MOB"
+        );
+        assert_eq!(
+            normalize_url("primary_server_url", "https://host/qrCheck.do"),
+            "https://host/qrCheck.do"
+        );
+    }
+
+    #[test]
+    fn v0_step_normalizes_urls() {
+        let mut config = version_0_config();
+        migrate_v0_normalize_urls(&mut config);
+
+        assert_eq!(
+            config["primary_server_url"],
+            "http://k-rsv.snu.ac.kr:8011/NEW_SNU_BOOKING"
+        );
+        assert_eq!(
+            config["secondary_server_url"],
+            "http://k-rsv.snu.ac.kr:8012/SEATAPI"
+        );
+        assert_eq!(config["qr_server_url"], "https://libapp.snu.ac.kr/SNU_MOB");
+        assert_eq!(
+            config["rfid_server_url"],
+            "https://libapp.snu.ac.kr/SNU_MOB"
+        );
+        assert_eq!(config["manager_ip_url"], "http://192.168.1.3:5841");
+    }
+
+    #[test]
+    fn v0_step_leaves_missing_and_non_string_urls_alone() {
+        let mut config = json!({ "primary_server_url": null });
+        migrate_v0_normalize_urls(&mut config);
+        assert_eq!(config, json!({ "primary_server_url": null }));
+    }
+
+    #[test]
+    fn v1_step_adds_missing_sections_with_defaults() {
+        let mut config = version_0_config();
+        migrate_v1_add_defaults(&mut config);
+
+        assert_eq!(config["printer_profiles"].as_array().map(Vec::len), Some(2));
+        assert_eq!(config["print_history"]["max_entries"], 20);
+        assert_eq!(config["idle"]["attract_after_secs"], 120);
+        assert_eq!(config["occupancy"]["retention_days"], 90);
+        // Values already in the file are kept
+        assert_eq!(config["machineId"], "kiosk-1");
+        assert_eq!(config["machineName"], "Main hall");
+    }
+
+    #[test]
+    fn v1_step_adds_missing_popup_timers_after_existing_ids() {
+        let mut config = version_0_config();
+        migrate_v1_add_defaults(&mut config);
+
+        let timers = config["popup_timers"].as_array().unwrap();
+        let names: Vec<&str> = timers.iter().filter_map(|t| t["name"].as_str()).collect();
+        assert_eq!(names.len(), default_popup_timers().len());
+        assert!(names.contains(&"SESSION TIMER REMINDER"));
+
+        let log_out = timers
+            .iter()
+            .find(|t| t["name"] == "LOG OUT TIMER")
             .unwrap();
-        e
-    })?;
+        assert_eq!(log_out["time"], 120);
+        assert_eq!(log_out["state"], false);
 
-    file.write_all(updated_content.as_bytes())?;
+        let mut ids: Vec<u64> = timers.iter().filter_map(|t| t["ID"].as_u64()).collect();
+        let count = ids.len();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), count, "timer IDs must stay unique");
+        assert_eq!(ids.iter().filter(|id| **id > 7).count(), count - 2);
+    }
 
-    logger
-        .log(
-            LogLevel::INFO,
-            &format!("Config updated successfully for key: {}", key),
+    #[test]
+    fn v1_step_adds_sensor_settings_to_the_human_sensor_entry_only() {
+        let mut config = version_0_config();
+        migrate_v1_add_defaults(&mut config);
+
+        let entries = config["serialdata"].as_array().unwrap();
+        assert!(entries[0].get("sensor").is_none());
+        assert_eq!(
+            entries[1]["sensor"],
+            serde_json::to_value(HumanSensorSettings::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn migration_keeps_human_sensor_detection_off_when_missing() {
+        let mut config = version_0_config();
+        config.as_object_mut().unwrap().remove("humanSensorDetection");
+        migrate_config(&mut config, 0);
+
+        assert!(config.get("humanSensorDetection").is_none());
+        let config: Config = serde_json::from_value(config).unwrap();
+        assert!(!config.humanSensorDetection);
+    }
+
+    #[test]
+    fn v1_step_does_not_invent_a_machine_id() {
+        let mut config = version_0_config();
+        config.as_object_mut().unwrap().remove("machineId");
+        migrate_config(&mut config, 0);
+
+        assert!(config.get("machineId").is_none());
+        assert!(serde_json::from_value::<Config>(config).is_err());
+    }
+
+    #[test]
+    fn v2_defaults_are_a_complete_config_apart_from_the_machine_id() {
+        let mut defaults: Value = serde_json::from_str(V2_DEFAULTS).unwrap();
+        defaults["machineId"] = json!("kiosk-1");
+        let config: Config = serde_json::from_value(defaults).unwrap();
+        assert!(validate_config(&config).is_empty());
+    }
+
+    #[test]
+    fn migration_chain_upgrades_version_0_to_current() {
+        let mut config = version_0_config();
+        migrate_config(&mut config, 0);
+
+        assert_eq!(schema_version(&config), CURRENT_SCHEMA_VERSION);
+        let config: Config = serde_json::from_value(config).unwrap();
+        assert_eq!(config.schema_version, CURRENT_SCHEMA_VERSION);
+        assert_eq!(config.qr_server_url, "https://libapp.snu.ac.kr/SNU_MOB");
+        assert_eq!(config.machineId, "kiosk-1");
+    }
+
+    #[test]
+    fn migration_chain_resumes_from_intermediate_version() {
+        let mut config = version_0_config();
+        config["schema_version"] = json!(1);
+        migrate_config(&mut config, 1);
+
+        assert_eq!(schema_version(&config), CURRENT_SCHEMA_VERSION);
+        // The URL step is skipped for a version 1 file
+        assert_eq!(
+            config["qr_server_url"],
+            "https://libapp.snu.ac.kr/SNU_MOB/qrCheck.do"
+        );
+        assert!(config.get("idle").is_some());
+    }
+
+    #[test]
+    fn current_config_needs_no_migration() {
+        let mut config = serde_json::to_value(default_config()).unwrap();
+        let before = config.clone();
+        migrate_config(&mut config, CURRENT_SCHEMA_VERSION);
+        assert_eq!(config, before);
+        assert_eq!(MIGRATIONS.len() as u32, CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn merge_patch_follows_rfc_7396() {
+        let mut target = json!({"a": "b", "c": {"d": "e", "f": "g"}, "list": [1, 2]});
+        merge_patch(
+            &mut target,
+            &json!({"a": "z", "c": {"f": null}, "list": [3]}),
+        );
+        assert_eq!(target, json!({"a": "z", "c": {"d": "e"}, "list": [3]}));
+    }
+
+    #[test]
+    fn patch_reports_changed_paths() {
+        let current = default_config();
+        let update = patch_config(
+            &current,
+            &json!({
+                "debug_mode": true,
+                "idle": {"attract_after_secs": 45},
+                "qr_server_url": "https://example.com/SNU_MOB/qrCheck.do"
+            }),
         )
         .unwrap();
-    Ok(())
+
+        assert!(update.config.debug_mode);
+        assert_eq!(update.config.qr_server_url, "https://example.com/SNU_MOB");
+        assert_eq!(
+            update.changed,
+            vec!["debug_mode", "idle.attract_after_secs", "qr_server_url"]
+        );
+    }
+
+    #[test]
+    fn patch_rejects_unknown_and_invalid_values() {
+        let current = default_config();
+        assert_eq!(
+            patch_config(&current, &json!({"idle": {"no_such_key": 1}})).unwrap_err(),
+            "Unknown config key: idle.no_such_key"
+        );
+        assert!(patch_config(&current, &json!({"primary_server_url": "ftp://host"})).is_err());
+        assert!(patch_config(&current, &json!({"kiosk_mode": "yes"})).is_err());
+        assert!(patch_config(&current, &json!([1])).is_err());
+    }
 }
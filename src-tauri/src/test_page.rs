@@ -0,0 +1,162 @@
+use crate::config_store::ConfigStore;
+use crate::kioskSetting::RELEASE_VERSION;
+use crate::logger::{LogLevel, Logger};
+use crate::paper::PaperTracker;
+use crate::printer::{find_printer, resolve_printer_profile, run_print_job, PrintCommand};
+use crate::transport::{open_printer_output, PrinterStatus};
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::State;
+
+const KOREAN_SAMPLE: &str = "한글 출력 테스트: 가나다라마바사 0123456789";
+
+/// Result of `print_test_page`, shown on the settings screen
+#[derive(Debug, Clone, Serialize)]
+pub struct TestPageReport {
+    port: String,
+    baud_rate: u32,
+    profile: String,
+    status: PrinterStatus,
+}
+
+fn describe(value: Option<bool>, yes: &str, no: &str) -> String {
+    match value {
+        Some(true) => yes.to_string(),
+        Some(false) => no.to_string(),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Print a self-test page on the configured PRINTER so installers can check
+/// the port, profile, Korean encoding and code rendering in one go
+#[tauri::command]
+pub fn print_test_page(
+    logger: State<'_, Arc<Logger>>,
+    paper: State<'_, Arc<PaperTracker>>,
+    config: State<'_, Arc<ConfigStore>>,
+) -> Result<TestPageReport, String> {
+    let logger = Arc::clone(&logger);
+    let config = config.get();
+    let printer = find_printer(&config)?;
+    let profile = resolve_printer_profile(&config, &printer.port, None)?;
+
+    logger
+        .log(
+            LogLevel::INFO,
+            &format!(
+                "Printing test page on {} at {} baud with profile {}",
+                printer.port, printer.baudrate, profile.name
+            ),
+        )
+        .ok();
+
+    // Ask for the status before printing so a jammed or offline printer still reports it
+    let status =
+        match open_printer_output(&profile.transport, &printer.port, printer.baudrate, &logger) {
+            Ok(mut output) => output.status(),
+            Err(e) => {
+                logger
+                    .log(
+                        LogLevel::WARN,
+                        &format!("Could not read printer status: {}", e),
+                    )
+                    .ok();
+                PrinterStatus::default()
+            }
+        };
+
+    let ports: Vec<Vec<String>> = config
+        .serialdata
+        .iter()
+        .map(|entry| {
+            vec![
+                entry.name.clone(),
+                entry.port.clone(),
+                entry.baudrate.to_string(),
+            ]
+        })
+        .collect();
+
+    let mut commands = vec![
+        PrintCommand::new("clear_all", None),
+        PrintCommand::new("alignment", Some("center")),
+        PrintCommand::new("bold", None),
+        PrintCommand::new("medium_text", None),
+        PrintCommand::new("text", Some("TEST PAGE\n")),
+        PrintCommand::new("normal_text", None),
+        PrintCommand::new("unbold", None),
+        PrintCommand::new("alignment", Some("left")),
+        PrintCommand::new("blank_line", None),
+        PrintCommand::two_column("Machine ID", &config.machineId),
+        PrintCommand::two_column("Machine name", &config.machineName),
+        PrintCommand::two_column("App version", RELEASE_VERSION),
+        PrintCommand::two_column(
+            "Printer",
+            &format!("{} @ {}", printer.port, printer.baudrate),
+        ),
+        PrintCommand::two_column("Profile", &profile.name),
+        PrintCommand::new("blank_line", None),
+        PrintCommand::new("bold", None),
+        PrintCommand::new("text", Some("Serial ports\n")),
+        PrintCommand::new("unbold", None),
+        PrintCommand {
+            rows: Some(ports),
+            ..PrintCommand::new("table", None)
+        },
+        PrintCommand::new("blank_line", None),
+        PrintCommand::new("korean_text", Some(KOREAN_SAMPLE)),
+        PrintCommand::new("blank_line", None),
+        PrintCommand::new("bold", None),
+        PrintCommand::new("text", Some("Printer status\n")),
+        PrintCommand::new("unbold", None),
+        PrintCommand::two_column("Online", &describe(status.online, "yes", "no")),
+        PrintCommand::two_column("Cover", &describe(status.cover_open, "open", "closed")),
+        PrintCommand::two_column(
+            "Paper near end",
+            &describe(status.paper_near_end, "yes", "no"),
+        ),
+        PrintCommand::two_column("Paper end", &describe(status.paper_end, "yes", "no")),
+        PrintCommand::new("blank_line", None),
+        PrintCommand::new("alignment", Some("center")),
+        PrintCommand::new("qr_code", Some(&config.machineId)),
+        PrintCommand::new("barcode", Some(&config.machineId)),
+        PrintCommand::new("alignment", Some("left")),
+        PrintCommand::new("cut", None),
+    ];
+    // Skip codes the machine ID can't be encoded in rather than fail the whole page
+    if config.machineId.is_empty() {
+        commands.retain(|c| c.commandType != "qr_code");
+    }
+    if config.machineId.is_empty() || !config.machineId.bytes().all(|b| (0x20..0x7F).contains(&b)) {
+        commands.retain(|c| c.commandType != "barcode");
+    }
+
+    let outcome = run_print_job(
+        &config,
+        &printer.port,
+        printer.baudrate,
+        Some(&profile.name),
+        &commands,
+        &logger,
+    )
+    .map_err(|e| {
+        logger
+            .log_error(
+                &format!("Test page failed: {}", e),
+                file!(),
+                "print_test_page",
+                line!(),
+            )
+            .ok();
+        e.to_string()
+    })?;
+    paper.record(&outcome);
+
+    logger.log(LogLevel::INFO, "Test page printed").ok();
+    Ok(TestPageReport {
+        port: printer.port.clone(),
+        baud_rate: printer.baudrate,
+        profile: profile.name,
+        status,
+    })
+}
@@ -0,0 +1,165 @@
+use crate::logger::{LogLevel, Logger};
+use crate::store::{get_config_dir, PrinterTransport};
+use serde::Serialize;
+use serialport::{ClearBuffer, DataBits, Parity, SerialPort, StopBits};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+const STATUS_TIMEOUT: Duration = Duration::from_millis(500);
+const PRINTER_STATUS: &[u8] = b"\x10\x04\x01"; // DLE EOT 1 - printer status
+const OFFLINE_STATUS: &[u8] = b"\x10\x04\x02"; // DLE EOT 2 - offline cause status
+const ROLL_SENSOR_STATUS: &[u8] = b"\x10\x04\x04"; // DLE EOT 4 - roll paper sensor status
+const OFFLINE_BIT: u8 = 0x08;
+const COVER_OPEN_BIT: u8 = 0x04;
+const NEAR_END_BITS: u8 = 0x0C;
+const PAPER_END_BITS: u8 = 0x60;
+
+/// Real-time status of a printer; None where the printer didn't answer
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct PrinterStatus {
+    pub online: Option<bool>,
+    pub cover_open: Option<bool>,
+    pub paper_near_end: Option<bool>,
+    pub paper_end: Option<bool>,
+}
+
+/// Byte sink for a printer, whatever it is connected through
+pub trait PrinterOutput: Write + Send {
+    /// Send a real-time status request and read the one byte reply.
+    /// Returns None when the transport cannot read or the printer doesn't answer.
+    fn request_status(&mut self, _request: &[u8]) -> Option<u8> {
+        None
+    }
+
+    /// Ask the roll paper sensor whether the paper is nearly used up, if the printer has one
+    fn paper_near_end(&mut self) -> Option<bool> {
+        self.request_status(ROLL_SENSOR_STATUS)
+            .map(|status| status & NEAR_END_BITS != 0)
+    }
+
+    /// Query the printer, offline cause and roll sensor status
+    fn status(&mut self) -> PrinterStatus {
+        let printer = self.request_status(PRINTER_STATUS);
+        let offline = self.request_status(OFFLINE_STATUS);
+        let roll = self.request_status(ROLL_SENSOR_STATUS);
+        PrinterStatus {
+            online: printer.map(|status| status & OFFLINE_BIT == 0),
+            cover_open: offline.map(|status| status & COVER_OPEN_BIT != 0),
+            paper_near_end: roll.map(|status| status & NEAR_END_BITS != 0),
+            paper_end: roll.map(|status| status & PAPER_END_BITS != 0),
+        }
+    }
+}
+
+struct SerialOutput(Box<dyn SerialPort>);
+
+impl Write for SerialOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl PrinterOutput for SerialOutput {
+    fn request_status(&mut self, request: &[u8]) -> Option<u8> {
+        self.0.clear(ClearBuffer::Input).ok()?;
+        self.0.write_all(request).ok()?;
+        self.0.flush().ok()?;
+        let previous_timeout = self.0.timeout();
+        self.0.set_timeout(STATUS_TIMEOUT).ok()?;
+        let mut reply = [0u8; 1];
+        let result = self.0.read_exact(&mut reply);
+        self.0.set_timeout(previous_timeout).ok();
+        result.ok().map(|_| reply[0])
+    }
+}
+
+impl PrinterOutput for TcpStream {
+    fn request_status(&mut self, request: &[u8]) -> Option<u8> {
+        self.write_all(request).ok()?;
+        self.flush().ok()?;
+        self.set_read_timeout(Some(STATUS_TIMEOUT)).ok()?;
+        let mut reply = [0u8; 1];
+        self.read_exact(&mut reply).ok().map(|_| reply[0])
+    }
+}
+
+impl PrinterOutput for File {}
+
+/// Open the output selected by the printer profile.
+/// `port_name` and `baud_rate` come from the print job and are only used by the serial transport.
+pub fn open_printer_output(
+    transport: &PrinterTransport,
+    port_name: &str,
+    baud_rate: u32,
+    logger: &Arc<Logger>,
+) -> Result<Box<dyn PrinterOutput>, String> {
+    match transport {
+        PrinterTransport::Serial => {
+            let port = serialport::new(port_name, baud_rate)
+                .data_bits(DataBits::Eight)
+                .stop_bits(StopBits::One)
+                .parity(Parity::None)
+                .timeout(WRITE_TIMEOUT)
+                .open()
+                .map_err(|e| format!("Failed to open port {}: {}", port_name, e))?;
+            Ok(Box::new(SerialOutput(port)))
+        }
+        PrinterTransport::Tcp { host, port } => {
+            let address = (host.as_str(), *port)
+                .to_socket_addrs()
+                .map_err(|e| format!("Failed to resolve printer {}:{}: {}", host, port, e))?
+                .next()
+                .ok_or_else(|| format!("No address found for printer {}:{}", host, port))?;
+            let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)
+                .map_err(|e| format!("Failed to connect to printer {}: {}", address, e))?;
+            stream
+                .set_write_timeout(Some(WRITE_TIMEOUT))
+                .map_err(|e| e.to_string())?;
+            Ok(Box::new(stream))
+        }
+        PrinterTransport::Usb { device } => {
+            let file = OpenOptions::new()
+                .write(true)
+                .open(device)
+                .map_err(|e| format!("Failed to open printer device {}: {}", device, e))?;
+            Ok(Box::new(file))
+        }
+        PrinterTransport::File { path } => {
+            let path = resolve_output_path(path, logger)?;
+            logger
+                .log(
+                    LogLevel::INFO,
+                    &format!("Writing print job to file: {}", path.display()),
+                )
+                .ok();
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .map_err(|e| {
+                    format!("Failed to open print output file {}: {}", path.display(), e)
+                })?;
+            Ok(Box::new(file))
+        }
+    }
+}
+
+// Relative file paths are kept next to the config file
+fn resolve_output_path(path: &str, logger: &Arc<Logger>) -> Result<PathBuf, String> {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        return Ok(path);
+    }
+    let config_dir = get_config_dir(Arc::clone(logger)).map_err(|e| e.to_string())?;
+    Ok(config_dir.join(path))
+}
@@ -8,6 +8,7 @@ import GlobalShortcuts from "./global/GlobalShortcuts";
 import { VoiceProvider } from "./context/voiceContext";
 import { NavigateProvider } from "./context/NavigateContext";
 import GlobalHeadphoneMonitor from "./components/GlobalHeadphoneMonitor";
+import GlobalIdleMonitor from "./components/GlobalIdleMonitor";
 import { SerialPortProvider } from "./context/SerialPortContext";
 import {initializeApi} from "./services/api";
 import React, {useEffect} from "react";
@@ -37,6 +38,7 @@ function App() {
             <SerialPortProvider>
               <GlobalShortcuts />
               <GlobalHeadphoneMonitor/>
+              <GlobalIdleMonitor />
               <AppRoutes />
             </SerialPortProvider>
           </NavigateProvider>
@@ -0,0 +1,104 @@
+import { useEffect, useRef, useState } from "react";
+import { useDispatch, useSelector } from "react-redux";
+import { useLocation } from "react-router-dom";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useTranslation } from "react-i18next";
+import BgMainImage from "../assets/images/BgMain.jpg";
+import { useNavigateContext } from "../context/NavigateContext";
+import { logout } from "../redux/slice/authSlice";
+
+const ACTIVITY_THROTTLE_MS = 1000;
+
+/**
+ * Hands the logout countdown to the backend idle controller.
+ * The backend pauses it while the human sensor sees someone and emits
+ * session-expired when the session should end, and attract-mode while
+ * nobody has been at the kiosk for a while.
+ */
+const GlobalIdleMonitor = () => {
+  const dispatch = useDispatch();
+  const navigate = useNavigateContext();
+  const location = useLocation();
+  const { t } = useTranslation();
+  const isAuthenticated = useSelector((state) => state.auth.isAuthenticated);
+  const lastActivityRef = useRef(0);
+  const [attractMode, setAttractMode] = useState(false);
+
+  const screen = location.pathname.startsWith("/floor") ? "floor" : "main";
+
+  // Start or end the backend session as the user logs in, out or changes screen
+  useEffect(() => {
+    const command = isAuthenticated ? "start_idle_session" : "end_idle_session";
+    const args = isAuthenticated ? { screen } : {};
+    invoke(command, args).catch((error) =>
+      console.error(`Failed to ${command}:`, error)
+    );
+  }, [isAuthenticated, screen]);
+
+  // Report touches and key presses so the backend can restart the countdown
+  useEffect(() => {
+    const handleActivity = () => {
+      const now = Date.now();
+      if (now - lastActivityRef.current < ACTIVITY_THROTTLE_MS) return;
+      lastActivityRef.current = now;
+      invoke("report_user_activity").catch((error) =>
+        console.error("Failed to report user activity:", error)
+      );
+    };
+
+    window.addEventListener("pointerdown", handleActivity);
+    window.addEventListener("keydown", handleActivity);
+    return () => {
+      window.removeEventListener("pointerdown", handleActivity);
+      window.removeEventListener("keydown", handleActivity);
+    };
+  }, []);
+
+  useEffect(() => {
+    let isMounted = true;
+    let unlistenExpired;
+    let unlistenAttract;
+
+    const setupListeners = async () => {
+      try {
+        unlistenExpired = await listen("session-expired", () => {
+          if (!isMounted) return;
+          dispatch(logout());
+          navigate("/");
+        });
+        unlistenAttract = await listen("attract-mode", (event) => {
+          if (!isMounted) return;
+          setAttractMode(Boolean(event.payload?.active));
+        });
+      } catch (error) {
+        console.error("Error setting up idle listeners:", error);
+      }
+    };
+
+    setupListeners();
+
+    return () => {
+      isMounted = false;
+      if (typeof unlistenExpired === "function") unlistenExpired();
+      if (typeof unlistenAttract === "function") unlistenAttract();
+    };
+  }, [dispatch, navigate]);
+
+  if (!attractMode) return null;
+
+  // Any touch reports activity above, which makes the backend leave attract mode
+  return (
+    <div
+      className="fixed inset-0 z-[10000] flex items-center justify-center bg-cover bg-center"
+      style={{ backgroundImage: `url(${BgMainImage})` }}
+      onPointerDown={() => setAttractMode(false)}
+    >
+      <p className="px-10 py-6 rounded-lg bg-[#000000a1] text-white text-[48px] font-bold">
+        {t("translations.Touch the screen to start")}
+      </p>
+    </div>
+  );
+};
+
+export default GlobalIdleMonitor;
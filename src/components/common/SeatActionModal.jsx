@@ -25,7 +25,6 @@ import { useTranslation } from "react-i18next";
 import { useVoice } from "../../context/voiceContext";
 import { useSerialPort } from "../../context/SerialPortContext";
 import { logout } from "../../redux/slice/authSlice";
-import { getPrintData } from "../layout/floor/PrintSlip";
 import { FaCheck, FaTimes } from "react-icons/fa";
 
 // Label-only element types that should be skipped during keyboard nav
@@ -69,7 +68,7 @@ const SeatActionModal = ({
     const navigate = useNavigate();
     const dispatch = useDispatch();
     const lastSpokenRef = useRef("");
-    const { writeToSerialPort, serialPortsData } = useSerialPort();
+    const { printBookingSlip } = useSerialPort();
 
     const languageCode = localStorage.getItem("lang") === "ko" ? "ko" : "en";
     const uiDateFormat = languageCode === "ko" ? DATE_FORMATS.KO_DATETIME : DATE_FORMATS.DATETIME;
@@ -265,38 +264,27 @@ const SeatActionModal = ({
 
     const handlePrint = useCallback(async () => {
         try {
-            const dateFormat = languageCode === "ko" ? DATE_FORMATS.KO_DATETIME : DATE_FORMATS.DATETIME;
-            const formattedData = {
-                USER_NAME: userInfo?.NAME || "",
-                SCHOOL_NO: userInfo?.SCHOOLNO || "",
-                BOOKING_DATE: formatDate(startTime, dateFormat),
-                ROOM: seatInfo?.FLOOR_NAME || "",
-                SEAT_NO: seat?.VNAME || bookingSeatInfo?.SEAT_VNAME || seatInfo?.SEAT_VNAME || "",
-                CHECKIN_TIME: formatDate(startTime, dateFormat),
-                CHECKOUT_TIME: endTime
-                    ? formatDate(endTime, dateFormat)
+            const slip = {
+                user_name: userInfo?.NAME || null,
+                school_no: userInfo?.SCHOOLNO || "",
+                room: seatInfo?.FLOOR_NAME || "",
+                seat: seat?.VNAME || bookingSeatInfo?.SEAT_VNAME || seatInfo?.SEAT_VNAME || "",
+                check_in: startTime ? formatDate(startTime, DATE_FORMATS.DATETIME) : null,
+                expiry: endTime
+                    ? formatDate(endTime, DATE_FORMATS.DATETIME)
                     : bookingSeatInfo?.USEEXPIRE
-                        ? formatDate(bookingSeatInfo.USEEXPIRE, dateFormat)
-                        : "",
-                BARCODE: userInfo?.SCHOOLNO || "",
-                USER_ID_QR: userInfo?.SCHOOLNO || "",
+                        ? formatDate(bookingSeatInfo.USEEXPIRE, DATE_FORMATS.DATETIME)
+                        : null,
+                qr: userInfo?.SCHOOLNO || null,
+                language: languageCode,
             };
-            const printerConfig = Array.isArray(serialPortsData)
-                ? serialPortsData.find((p) => p.name === "PRINTER")
-                : null;
-            const printData = getPrintData(formattedData, t);
-            const printOptions = {
-                port_name: printerConfig?.port,
-                baud_rate: printerConfig?.baudrate,
-                commands: printData.commands.map((c) => ({ type: c.type, value: c.value || null })),
-            };
-            await writeToSerialPort(printerConfig, printOptions);
+            await printBookingSlip(slip);
             dispatch(logout());
             navigate("/");
         } catch (err) {
             console.error("Error printing:", err);
         }
-    }, [userInfo, seat, bookingSeatInfo, seatInfo, startTime, endTime, serialPortsData, writeToSerialPort, t, dispatch, navigate, languageCode]);
+    }, [userInfo, seat, bookingSeatInfo, seatInfo, startTime, endTime, printBookingSlip, dispatch, navigate, languageCode]);
 
     const handleEnterPress = useCallback((focusedElement) => {
         if (!focusedElement) return;
@@ -45,6 +45,7 @@ export const SerialPortProvider = ({ children }) => {
 
   // Sensor & Device States
   const [humanDetected, setHumanDetected] = useState(false);                // Human presence sensor state
+  const [humanSensorActive, setHumanSensorActive] = useState(true);        // False while the sensor schedule switches it off
   const [rfidMessages, setRfidMessages] = useState([]);                     // RFID scan history
 
   // UI States (for future modal implementations)
@@ -570,6 +571,24 @@ export const SerialPortProvider = ({ children }) => {
     }
   };
 
+  /**
+   * Print a booking slip on the configured printer.
+   * The slip layout and translations live in the Rust `print_booking_slip` command.
+   *
+   * @param {Object} slip - user_name, school_no, room, seat, check_in, expiry, qr, language
+   * @returns {boolean} Success status
+   */
+  const printBookingSlip = async (slip) => {
+    try {
+      await invoke("stop_serial_reading");
+      await invoke("print_booking_slip", { slip });
+      return true;
+    } catch (error) {
+      console.error("Error printing booking slip:", error);
+      return false;
+    }
+  };
+
   useEffect(() => {
     const initialize = async () => {
       try {
@@ -869,20 +888,28 @@ export const SerialPortProvider = ({ children }) => {
   }, [dispatch, location.pathname, speak, t]);
 
   /**
-   * Listen for human sensor state changes
+   * Listen for debounced presence changes from the human sensor
    * Tracks human presence detection for kiosk interaction
    */
   useEffect(() => {
     let isMounted = true;
-    let unlisten;
+    let unlistenArrived;
+    let unlistenLeft;
+    let unlistenStatus;
 
     const setupHumanSensorListener = async () => {
       try {
-        unlisten = await listen("human-sensor-state", (event) => {
+        unlistenArrived = await listen("person-arrived", () => {
           if (!isMounted) return;
-
-          const { detected } = event.payload;
-          setHumanDetected(detected);
+          setHumanDetected(true);
+        });
+        unlistenLeft = await listen("person-left", () => {
+          if (!isMounted) return;
+          setHumanDetected(false);
+        });
+        unlistenStatus = await listen("human-sensor-status", (event) => {
+          if (!isMounted) return;
+          setHumanSensorActive(event.payload.active);
         });
       } catch (error) {
         console.error("Error setting up human sensor listener:", error);
@@ -893,7 +920,9 @@ export const SerialPortProvider = ({ children }) => {
 
     return () => {
       isMounted = false;
-      if (typeof unlisten === "function") unlisten();
+      if (typeof unlistenArrived === "function") unlistenArrived();
+      if (typeof unlistenLeft === "function") unlistenLeft();
+      if (typeof unlistenStatus === "function") unlistenStatus();
     };
   }, []);
 
@@ -963,10 +992,12 @@ export const SerialPortProvider = ({ children }) => {
 
         // Sensor Data
         humanDetected,
+        humanSensorActive,
         rfidMessages,
 
         // Functions
         writeToSerialPort,
+        printBookingSlip,
         stopSerialReading,
         performSystemCleanup,
 
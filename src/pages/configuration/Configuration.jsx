@@ -176,27 +176,18 @@ const Configuration = () => {
         setIsSaving(true);
 
         try {
-            // 1️⃣ Save manager IP URL
-            await invoke("update_config", {
-                key: "manager_ip_url",
-                value: formData.managerIpUrl
-            });
-
-            // 2️⃣ Save kiosk mode
-            await invoke("update_config", {
-                key: "kiosk_mode",
-                value: formData.kioskMode.toString()
-            });
-
-            // 3️⃣ Save serial devices
             const serialDataToSave = serialDevices.map((device) => ({
                 ...device,
                 baudrate: parseInt(device.baudrate, 10),
             }));
 
+            // Saved as one merge patch, so the backend validates and writes it all at once
             await invoke("update_config", {
-                key: "serialdata",
-                value: JSON.stringify(serialDataToSave),
+                patch: {
+                    manager_ip_url: formData.managerIpUrl,
+                    kiosk_mode: formData.kioskMode,
+                    serialdata: serialDataToSave,
+                },
             });
 
             // ✅ SUCCESS (no API call here)
@@ -206,7 +197,8 @@ const Configuration = () => {
         } catch (error) {
             console.error("Failed to save configuration:", error);
             setShowConfirmationModal(false);
-            setErrorMessage(t("Failed to save configuration"));
+            // Rejected writes carry the backend's validation errors
+            setErrorMessage(`${t("Failed to save configuration")}: ${error}`);
             setShowErrorModal(true);
         } finally {
             setIsSaving(false);
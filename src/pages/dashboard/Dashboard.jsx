@@ -62,7 +62,7 @@ const Dashboard = () => {
   const { speak, stop } = useVoice();
   const { t } = useTranslation();
   const { setCurrentFloor } = useFloorData(null, null);
-  const { humanDetected } = useSerialPort();
+  const { humanDetected, humanSensorActive } = useSerialPort();
 
   // ─── Redux selectors ────────────────────────────────────────────────────
   const lang = useSelector((s) => s.lang.current);
@@ -414,13 +414,13 @@ const Dashboard = () => {
 
   // Human sensor
   useEffect(() => {
-    if (!HUMAN_SENSOR_DETECTION || typeof humanDetected !== "boolean") return;
+    if (!HUMAN_SENSOR_DETECTION || !humanSensorActive || typeof humanDetected !== "boolean") return;
     if (humanDetected && !lastHumanStateRef.current) speakMainScreen();
     lastHumanStateRef.current = humanDetected;
     if (humanDetected) return;
     const id = setInterval(() => speakMainScreen(), 60000 * 3);
     return () => clearInterval(id);
-  }, [humanDetected, speakMainScreen]);
+  }, [humanDetected, humanSensorActive, speakMainScreen]);
 
   // Earphone injection → logout
   useEffect(() => {
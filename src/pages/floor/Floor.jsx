@@ -1,6 +1,8 @@
 import { useEffect, useState, useRef, useCallback, useMemo } from "react";
 import { useLocation, useNavigate, useParams } from "react-router-dom";
 import { useDispatch, useSelector } from "react-redux";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import BgMainImage from "../../assets/images/BgMain.jpg";
 import { clearUserInfo, setUserInfo } from "../../redux/slice/userInfo";
 import LoadingSpinner from "../../components/common/LoadingSpinner";
@@ -14,7 +16,7 @@ import FloorLegendBar from "../../components/layout/floor/FloorLegendBar";
 import FooterControls from "../../components/common/Footer";
 import {
   getFloorImageUrl, getKioskUserInfo, getSeatList, getImageBaseUrl,
-  getPopupTimers, initializeApi, setApiLang,
+  initializeApi, setApiLang,
 } from "../../services/api";
 import { MINI_MAP_LAYOUT, MINIMAP_CONFIG } from "../../utils/constant";
 import SeatActionModal from "../../components/common/SeatActionModal";
@@ -77,19 +79,13 @@ const Floor = () => {
   const [miniMapCursor,        setMiniMapCursor]        = useState(-1);
   const [mainContentCursor,    setMainContentCursor]    = useState(null);
 
-  // ─── Session / timer state ───────────────────────────────────────────────
-  const [floorTimerConfig,     setFloorTimerConfig]     = useState({ time: 180, state: true });
-  const [sessionReminderConfig, setSessionReminderConfig] = useState({ time: 60, state: true });
-  const [timeLeft,             setTimeLeft]             = useState(180);
+  // ─── Session state ───────────────────────────────────────────────────────
   const [showSessionReminder,  setShowSessionReminder]  = useState(false);
   const [sessionCursor,        setSessionCursor]        = useState(null);
-  const [resetTimerOnTouch,    setResetTimerOnTouch]    = useState(true);
 
   // ─── Refs ────────────────────────────────────────────────────────────────
   const mainImageRef          = useRef(null);
   const containerRef          = useRef(null);
-  const timerRef              = useRef(null);
-  const timeLeftRef           = useRef(180);
   const lastSpokenRef         = useRef("");
   const lastFocusedRegionRef  = useRef(null);
   const hasSpokenRoomRef      = useRef(false);
@@ -155,7 +151,7 @@ const Floor = () => {
     return labels[index] || labels[0];
   }, []);
 
-  // ─── Init: user info + timer config ──────────────────────────────────────
+  // ─── Init: user info + API config ────────────────────────────────────────
 
   useEffect(() => {
     const init = async () => {
@@ -168,16 +164,7 @@ const Floor = () => {
 
       try {
         await initializeApi();
-        const timers = getPopupTimers();
-        if (timers?.length) {
-          const floorCfg   = timers.find((t) => t.name === "LOG OUT FLOOR TIMER");
-          const reminderCfg = timers.find((t) => t.name === "SESSION TIMER REMINDER");
-          const resetCfg   = timers.find((t) => t.name === "RESET TIMER ON TOUCH");
-          if (floorCfg)   { setFloorTimerConfig(floorCfg); setTimeLeft(floorCfg.time); timeLeftRef.current = floorCfg.time; }
-          if (reminderCfg) setSessionReminderConfig(reminderCfg);
-          if (resetCfg)   setResetTimerOnTouch(resetCfg.state);
-        }
-      } catch (err) { console.error("Failed to load timer config:", err); }
+      } catch (err) { console.error("Failed to initialize API:", err); }
     };
     init();
   }, [dispatch]);
@@ -430,46 +417,31 @@ const Floor = () => {
     }
   }, [focusedRegion, mainContentCursor, displayableSectors, visibleSeats, currentFloor, selectedSector, speak, stop, t, lang, getSectorLabel]);
 
-  // ─── Idle timer ───────────────────────────────────────────────────────────
+  // ─── Session reminder ─────────────────────────────────────────────────────
+  // The backend idle controller runs the logout countdown (paused while the
+  // human sensor sees someone) and warns before the session expires; the
+  // logout itself is handled by GlobalIdleMonitor on session-expired.
 
   useEffect(() => {
-    if (!floorTimerConfig.state) return;
-    clearInterval(timerRef.current);
+    let isMounted = true;
+    let unlisten;
 
-    timerRef.current = setInterval(() => {
-      timeLeftRef.current -= 1;
-      if (sessionReminderConfig.state && timeLeftRef.current === sessionReminderConfig.time) {
-        setShowSessionReminder(true);
-      }
-      if (timeLeftRef.current <= 0) {
-        clearInterval(timerRef.current);
-        localStorage.removeItem("authenticated");
-        dispatch(clearUserInfo());
-        navigate("/");
-        return;
-      }
-      setTimeLeft(timeLeftRef.current);
-    }, 1000);
-
-    const resetTimer = () => {
-      if (isAnyModalOpen || !resetTimerOnTouch) return;
-      timeLeftRef.current = floorTimerConfig.time;
-      setTimeLeft(floorTimerConfig.time);
+    const setupListener = async () => {
+      try {
+        unlisten = await listen("session-expire-warning", (event) => {
+          if (!isMounted || event.payload?.screen !== "floor") return;
+          setShowSessionReminder(true);
+        });
+      } catch (err) { console.error("Error setting up session warning listener:", err); }
     };
 
-    if (resetTimerOnTouch) {
-      window.addEventListener("click", resetTimer, true);
-      window.addEventListener("touchstart", resetTimer, true);
-    }
+    setupListener();
 
     return () => {
-      clearInterval(timerRef.current);
-      if (resetTimerOnTouch) {
-        window.removeEventListener("click", resetTimer, true);
-        window.removeEventListener("touchstart", resetTimer, true);
-      }
+      isMounted = false;
+      if (typeof unlisten === "function") unlisten();
     };
-  }, [floorTimerConfig.state, sessionReminderConfig.state, resetTimerOnTouch, isAnyModalOpen]);
+  }, []);
 
   // ─── Action handlers ──────────────────────────────────────────────────────
 
@@ -557,12 +529,14 @@ const Floor = () => {
 
   const handleSessionEnter = useCallback((index) => {
     if (index === 0) {
-      timeLeftRef.current = floorTimerConfig.time;
-      setTimeLeft(floorTimerConfig.time);
+      // Restart the countdown even when touches don't reset it
+      invoke("start_idle_session", { screen: "floor" }).catch((err) =>
+        console.error("Failed to extend session:", err)
+      );
     }
     setShowSessionReminder(false);
     setIsAnyModalOpen(false);
-  }, [floorTimerConfig.time]);
+  }, []);
 
   // ─── Render ───────────────────────────────────────────────────────────────
 
@@ -690,7 +664,6 @@ const Floor = () => {
         isAnyModalOpen={isAnyModalOpen}
         showBack={showRoomView}
         onBack={backToFloorMap}
-        timer={timeLeft}
       />
 
       {/* ═══ SEAT BOOKING MODAL ═══ */}
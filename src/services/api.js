@@ -1,5 +1,6 @@
 import axios from "axios";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { fetch as tauriFetch } from '@tauri-apps/plugin-http';
 import { logEvent } from "../logger";
 
@@ -196,6 +197,61 @@ ApiClientSecondary.interceptors.request.use(KioskLangInterceptor);
 ApiClientQR.interceptors.request.use(KioskLangInterceptor);
 ApiClientRFID.interceptors.request.use(KioskLangInterceptor);
 
+// Settings cached by applyConfig; a config-changed event touching any of them re-reads the config
+const CACHED_CONFIG_KEYS = [
+  "machineId", "machineName", "primary_server_url", "secondary_server_url", "qr_server_url",
+  "rfid_server_url", "manager_ip_url", "popup_timers", "humanSensorDetection",
+];
+
+const applyConfig = async (config) => {
+  const { machineId, machineName, primary_server_url, secondary_server_url, qr_server_url, rfid_server_url, manager_ip_url, popup_timers, humanSensorDetection } = config;
+  managerIpUrl = manager_ip_url || "";
+  MACHINE_ID = machineId || "";
+  MACHINE_NAME = machineName || "";
+  PRIMARY_SERVER_URL = primary_server_url;
+  SECONDARY_SERVER_URL = secondary_server_url;
+  QR_SERVER_URL = qr_server_url || "https://libapp.snu.ac.kr/SNU_MOB";
+  RFID_SERVER_URL = rfid_server_url || "https://libapp.snu.ac.kr/SNU_MOB";
+  popupTimers = popup_timers || [];
+  HUMAN_SENSOR_DETECTION = humanSensorDetection ?? false;
+
+  console.log(config, "data")
+  console.log("Config Loaded:", {
+    PRIMARY_SERVER_URL,
+    SECONDARY_SERVER_URL
+  });
+
+  if (PRIMARY_SERVER_URL || SECONDARY_SERVER_URL) {
+    ApiClientManagerIP.defaults.baseURL = managerIpUrl;
+    ApiClientPrimary.defaults.baseURL = PRIMARY_SERVER_URL;
+    ApiClientSecondary.defaults.baseURL = SECONDARY_SERVER_URL;
+    ApiClientQR.defaults.baseURL = QR_SERVER_URL;
+    ApiClientRFID.defaults.baseURL = RFID_SERVER_URL;
+
+    // ✅ CRITICAL FIX: Sync server locale immediately after API initialization
+    console.log("🌍 Initializing server locale on startup...");
+    await syncServerLocale(runtimeLang);
+  }
+};
+
+// Pick up URL and timer changes made in the settings screen or in the config file
+const listenForConfigChanges = async () => {
+  try {
+    await listen("config-changed", async (event) => {
+      const keys = event.payload?.keys || [];
+      if (!keys.some((key) => CACHED_CONFIG_KEYS.includes(key))) return;
+      try {
+        await applyConfig(await invoke("read_config"));
+        await logEvent("info", `API config reloaded after changes to ${keys.join(", ")}`);
+      } catch (error) {
+        await logEvent("error", `Failed to reload API config: ${error.message || error}`);
+      }
+    });
+  } catch (error) {
+    console.error("Error setting up config change listener:", error);
+  }
+};
+
 export const initializeApi = async () => {
   if (!apiInitialized) {
     apiInitialized = (async () => {
@@ -203,36 +259,7 @@ export const initializeApi = async () => {
 
       do {
         try {
-          const config = await invoke("read_config");
-          const { machineId, machineName, primary_server_url, secondary_server_url, qr_server_url, rfid_server_url, manager_ip_url, popup_timers, humanSensorDetection } = config;
-          managerIpUrl = manager_ip_url || "";
-          MACHINE_ID = machineId || "";
-          MACHINE_NAME = machineName || "";
-          PRIMARY_SERVER_URL = primary_server_url;
-          SECONDARY_SERVER_URL = secondary_server_url;
-          QR_SERVER_URL = qr_server_url || "https://libapp.snu.ac.kr/SNU_MOB/qrCheck.do";
-          RFID_SERVER_URL = rfid_server_url || "https://libapp.snu.ac.kr/SNU_MOB/qrCheck.do";
-          popupTimers = popup_timers || [];
-          HUMAN_SENSOR_DETECTION = humanSensorDetection ?? false;
-
-
-          console.log(config, "data")
-          console.log("Config Loaded:", {
-            PRIMARY_SERVER_URL,
-            SECONDARY_SERVER_URL
-          });
-
-          if (PRIMARY_SERVER_URL || SECONDARY_SERVER_URL) {
-            ApiClientManagerIP.defaults.baseURL = managerIpUrl;
-            ApiClientPrimary.defaults.baseURL = PRIMARY_SERVER_URL;
-            ApiClientSecondary.defaults.baseURL = SECONDARY_SERVER_URL;
-            ApiClientQR.defaults.baseURL = QR_SERVER_URL;
-            ApiClientRFID.defaults.baseURL = RFID_SERVER_URL;
-
-            // ✅ CRITICAL FIX: Sync server locale immediately after API initialization
-            console.log("🌍 Initializing server locale on startup...");
-            await syncServerLocale(runtimeLang);
-          }
+          await applyConfig(await invoke("read_config"));
           await logEvent("info", "API initialized successfully");
           isInitialized = true;
         } catch (error) {
@@ -240,6 +267,7 @@ export const initializeApi = async () => {
           console.error("Failed to fetch configuration:", error);
         }
       } while (!isInitialized);
+      await listenForConfigChanges();
     })();
   }
   return apiInitialized;
@@ -260,6 +260,7 @@ const enTranslations = {
     Yes: "Yes",
     No: "No",
     "Session Extension": "Session Extension",
+    "Touch the screen to start": "Touch the screen to start",
     "키오스크 번호를 확인하세요.": "Please check the kiosk number.",
     Fixed: "Fixed",
     "Call Administrator": "Call Administrator",
@@ -291,6 +291,7 @@ const koTranslations = {
     Yes: "예",
     No: "아니요",
     "Session Extension": "세션 확장",
+    "Touch the screen to start": "화면을 터치하여 시작하세요",
     "키오스크 번호를 확인하세요.": "키오스크 번호를 확인하세요.",
     Fixed: "고정석",
     "Call Administrator": "관리자 호출",